@@ -1,4 +1,7 @@
+use crate::scanner::token::Token;
+use std::fmt::{Display, Formatter};
 use std::io;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,12 +12,140 @@ pub struct Error(#[from] pub ErrorType);
 pub enum ErrorType {
     #[error("Parsing error `{0}`")]
     Parse(String),
-    #[error("Interpreter error")]
-    Interpreter(String),
-    #[error("Token error")]
-    Token(String),
+    #[error("Interpreter error `{0}`{trace}", trace = .0.trace_lines())]
+    Interpreter(RuntimeError),
     #[error("io")]
     Io(#[from] io::Error),
     #[error("Scanner error `{0}`")]
     Scanner(String),
+    #[error("Serialization error `{0}`")]
+    Serialization(String),
+    #[error("Resolution error `{0}`")]
+    Resolve(String),
+    // the source ended inside an unclosed string, brace, paren or bracket, so more of it may
+    // follow. It holds the error to report if none does
+    #[error("{0}")]
+    Incomplete(Box<Error>),
+    // the script was stopped for going over one of the interpreter's limits. Scripts can't
+    // handle this one themselves
+    #[error("Limit exceeded `{0}`")]
+    Limit(LimitExceeded),
+}
+
+impl Error {
+    /// The error to report when no more input is coming: an `Incomplete` error becomes the
+    /// scanner or parser error it holds.
+    pub fn completed(self) -> Error {
+        match self {
+            Error(ErrorType::Incomplete(err)) => *err,
+            err => err,
+        }
+    }
+}
+
+const MAX_REPEATED_FRAMES: usize = 3;
+
+/// An error raised while running a script, with the calls that were in progress when it
+/// happened.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuntimeError {
+    pub message: String,
+    pub line: Option<usize>,
+    /// The lexeme of the token the error points at.
+    pub lexeme: Option<String>,
+    /// Innermost call first, ending with the top level of the script. Filled in as the error
+    /// leaves the innermost call.
+    pub trace: Vec<TraceFrame>,
+    /// Raised by a `throw`. The interpreter holds on to the thrown value until it's caught.
+    pub thrown: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceFrame {
+    pub function: String,
+    pub file: String,
+    pub line: usize,
+}
+
+impl RuntimeError {
+    pub fn new(message: &str) -> Self {
+        Self {
+            message: message.to_string(),
+            line: None,
+            lexeme: None,
+            trace: vec![],
+            thrown: false,
+        }
+    }
+
+    pub fn at(token: &Token, message: &str) -> Self {
+        Self {
+            line: Some(token.line()),
+            lexeme: Some(token.lexeme().to_string()),
+            ..Self::new(message)
+        }
+    }
+
+    // the trace, one frame per line under the message. Runs of the same frame, as deep
+    // recursion leaves, are cut short
+    fn trace_lines(&self) -> String {
+        let mut lines = String::new();
+        let mut repeats = 0;
+
+        for (i, frame) in self.trace.iter().enumerate() {
+            let is_repeat = i > 0 && self.trace[i - 1] == *frame;
+            repeats = if is_repeat { repeats + 1 } else { 0 };
+
+            if repeats < MAX_REPEATED_FRAMES {
+                lines.push_str(&format!("\n    at {}", frame));
+            }
+
+            let run_ends = self.trace.get(i + 1) != Some(frame);
+            if run_ends && repeats >= MAX_REPEATED_FRAMES {
+                lines.push_str(&format!(
+                    "\n    [previous frame repeated {} more times]",
+                    repeats + 1 - MAX_REPEATED_FRAMES
+                ));
+            }
+        }
+
+        lines
+    }
+}
+
+impl Display for RuntimeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (self.line, &self.lexeme) {
+            (Some(line), Some(lexeme)) => write!(f, "{} at {} {}", line, lexeme, self.message),
+            (Some(line), None) => write!(f, "{} {}", line, self.message),
+            (None, _) => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Which of the interpreter's limits a script went over.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LimitExceeded {
+    Steps(u64),
+    Timeout(Duration),
+    /// The memory limit, in bytes.
+    Memory(usize),
+}
+
+impl Display for LimitExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitExceeded::Steps(steps) => write!(f, "Ran more than {} steps.", steps),
+            LimitExceeded::Timeout(timeout) => write!(f, "Ran longer than {:?}.", timeout),
+            LimitExceeded::Memory(bytes) => {
+                write!(f, "Out of memory, using more than {} bytes.", bytes)
+            }
+        }
+    }
+}
+
+impl Display for TraceFrame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}:{})", self.function, self.file, self.line)
+    }
 }
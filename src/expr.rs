@@ -1,89 +1,181 @@
 use crate::scanner::token::Token;
 use crate::scanner::token_type;
+use crate::stmt::Stmt;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::rc::Rc;
 
-#[derive(Clone, Debug)]
-pub enum Expr<'expr> {
-    Assign(Assign<'expr>),
-    Binary(Binary<'expr>),
-    Call(Call<'expr>),
-    Get(Get<'expr>),
-    Grouping(GroupingExpr<'expr>),
-    Literal(LiteralExpr<'expr>),
-    Set(Set<'expr>),
-    Super(Super<'expr>),
-    This(This<'expr>),
-    Unary(Unary<'expr>),
-    Variable(Variable<'expr>),
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Expr {
+    Assign(Assign),
+    Binary(Binary),
+    Call(Call),
+    Comma(Comma),
+    Conditional(Conditional),
+    Error(ErrorExpr),
+    Function(FunctionExpr),
+    Get(Get),
+    Grouping(GroupingExpr),
+    Index(Index),
+    List(ListExpr),
+    Literal(LiteralExpr),
+    Logical(Logical),
+    Map(MapExpr),
+    Set(Set),
+    SetIndex(SetIndex),
+    Super(Super),
+    This(This),
+    Unary(Unary),
+    Variable(Variable),
 }
 
-#[derive(Clone, Debug)]
-pub struct Assign<'expr> {
-    pub name: Token<'expr>,
-    pub value: Box<Expr<'expr>>,
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Assign {
+    pub name: Token,
+    pub value: Box<Expr>,
+    /// How many scopes out from the assignment the variable lives, filled in by the resolver.
+    /// `None` means it's a global.
+    #[serde(skip)]
+    pub depth: Cell<Option<usize>>,
 }
 
-#[derive(Clone, Debug)]
-pub struct Binary<'expr> {
-    pub left: Box<Expr<'expr>>,
-    pub operator: Token<'expr>,
-    pub right: Box<Expr<'expr>>,
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Binary {
+    pub left: Box<Expr>,
+    pub operator: Token,
+    pub right: Box<Expr>,
 }
 
-#[derive(Clone, Debug)]
-pub struct Call<'expr> {
-    pub callee: Box<Expr<'expr>>,
-    pub parenthesis: Token<'expr>,
-    pub arguments: Vec<Expr<'expr>>,
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Call {
+    pub callee: Box<Expr>,
+    pub parenthesis: Token,
+    pub arguments: Vec<Expr>,
 }
 
-#[derive(Clone, Debug)]
-pub struct Get<'expr> {
-    pub object: Box<Expr<'expr>>,
-    pub name: Token<'expr>,
+/// `left, right`: evaluates `left` for its side effects, discards it and yields `right`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Comma {
+    pub left: Box<Expr>,
+    pub operator: Token,
+    pub right: Box<Expr>,
 }
 
-#[derive(Clone, Debug)]
-pub struct GroupingExpr<'expr> {
-    pub expression: Box<Expr<'expr>>,
+/// `condition ? then_branch : else_branch`: only the branch picked by the truthiness of
+/// `condition` is evaluated.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Conditional {
+    pub condition: Box<Expr>,
+    pub then_branch: Box<Expr>,
+    pub else_branch: Box<Expr>,
 }
 
-#[derive(Clone, Debug)]
-pub struct LiteralExpr<'expr> {
-    pub value: token_type::Literal<'expr>,
+/// Stands in for an expression that failed to parse. `tokens` is the span of input it covers.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ErrorExpr {
+    pub tokens: Vec<Token>,
+    pub message: String,
 }
 
-#[derive(Clone, Debug)]
-pub struct Logical<'expr> {
-    pub left: Box<Expr<'expr>>,
-    pub operator: Token<'expr>,
-    pub right: Box<Expr<'expr>>,
+/// `fun (params) { body }`: an anonymous function, parsed the same way as a named declaration.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FunctionExpr {
+    pub keyword: Token,
+    pub params: Vec<Token>,
+    pub body: Rc<Vec<Stmt>>,
 }
 
-#[derive(Clone, Debug)]
-pub struct Set<'expr> {
-    pub object: Box<Expr<'expr>>,
-    pub name: Token<'expr>,
-    pub value: Box<Expr<'expr>>,
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Get {
+    pub object: Box<Expr>,
+    pub name: Token,
 }
 
-#[derive(Clone, Debug)]
-pub struct Super<'expr> {
-    pub keyword: Token<'expr>,
-    pub method: Token<'expr>,
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GroupingExpr {
+    pub expression: Box<Expr>,
 }
 
-#[derive(Clone, Debug)]
-pub struct This<'expr> {
-    pub keyword: Token<'expr>,
+/// `object[index]`. `bracket` is the closing `]`, kept to locate runtime errors.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Index {
+    pub object: Box<Expr>,
+    pub bracket: Token,
+    pub index: Box<Expr>,
 }
 
-#[derive(Clone, Debug)]
-pub struct Unary<'expr> {
-    pub operator: Token<'expr>,
-    pub right: Box<Expr<'expr>>,
+/// `[a, b, c]`. `bracket` is the opening `[`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ListExpr {
+    pub bracket: Token,
+    pub elements: Vec<Expr>,
 }
 
-#[derive(Clone, Debug)]
-pub struct Variable<'expr> {
-    pub name: Token<'expr>,
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LiteralExpr {
+    pub value: token_type::Literal,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Logical {
+    pub left: Box<Expr>,
+    pub operator: Token,
+    pub right: Box<Expr>,
+}
+
+/// `{key: value, ...}` in expression position. A `{` that starts a statement is always a block.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MapExpr {
+    pub brace: Token,
+    pub entries: Vec<(Expr, Expr)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Set {
+    pub object: Box<Expr>,
+    pub name: Token,
+    pub value: Box<Expr>,
+}
+
+/// `object[index] = value`. `bracket` is the closing `]` of the index.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SetIndex {
+    pub object: Box<Expr>,
+    pub bracket: Token,
+    pub index: Box<Expr>,
+    pub value: Box<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Super {
+    pub keyword: Token,
+    pub method: Token,
+    /// How many scopes out the `super` binding of the enclosing class lives, filled in by the
+    /// resolver.
+    #[serde(skip)]
+    pub depth: Cell<Option<usize>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct This {
+    pub keyword: Token,
+    /// How many scopes out the `this` binding of the enclosing method lives, filled in by the
+    /// resolver.
+    #[serde(skip)]
+    pub depth: Cell<Option<usize>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Unary {
+    pub operator: Token,
+    pub right: Box<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Variable {
+    pub name: Token,
+    /// How many scopes out from the use the variable lives, filled in by the resolver. `None`
+    /// means it's a global.
+    #[serde(skip)]
+    pub depth: Cell<Option<usize>>,
 }
@@ -0,0 +1,46 @@
+use crate::interpreter::interpreter::Interpreter;
+use crate::interpreter::value::Value;
+use crate::Result;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+/// How many arguments a callable takes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Arity {
+    Fixed(usize),
+    /// Any number from the minimum up.
+    Variadic(usize),
+}
+
+impl Arity {
+    pub fn accepts(&self, count: usize) -> bool {
+        match self {
+            Arity::Fixed(arity) => count == *arity,
+            Arity::Variadic(min) => count >= *min,
+        }
+    }
+}
+
+/// Anything a Lox script can call. The interpreter checks the argument count against `arity`
+/// before calling, so implementations can rely on getting an accepted number of them.
+pub trait LoxCallable: Debug {
+    fn name(&self) -> &str;
+
+    fn arity(&self) -> Arity;
+
+    fn call(&self, interpreter: &Interpreter, arguments: Vec<Value>) -> Result<Value>;
+
+    /// The script the callable is written in. Natives have none and are left out of stack
+    /// traces.
+    fn file(&self) -> Option<Rc<str>> {
+        None
+    }
+
+    /// How the callable prints.
+    fn describe(&self) -> String {
+        match self.name() {
+            "" => String::from("<fn>"),
+            name => format!("<fn {}>", name),
+        }
+    }
+}
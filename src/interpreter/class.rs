@@ -0,0 +1,174 @@
+use crate::interpreter::callable::{Arity, LoxCallable};
+use crate::interpreter::function::LoxFunction;
+use crate::interpreter::heap::{address, Heap, HeapSize, Trace};
+use crate::interpreter::interpreter::Interpreter;
+use crate::interpreter::runtime_error;
+use crate::interpreter::value::Value;
+use crate::scanner::token::Token;
+use crate::Result;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::rc::{Rc, Weak};
+
+/// A class declared in Lox. Calling it makes an instance and runs `init` on it, if there is one.
+#[derive(Debug)]
+pub struct LoxClass {
+    name: String,
+    superclass: Option<Rc<LoxClass>>,
+    methods: HashMap<String, Rc<LoxFunction>>,
+    // the class itself, which its instances point back to
+    this: Weak<LoxClass>,
+}
+
+impl LoxClass {
+    pub fn new(
+        name: String,
+        superclass: Option<Rc<LoxClass>>,
+        methods: HashMap<String, Rc<LoxFunction>>,
+    ) -> Rc<Self> {
+        Rc::new_cyclic(|this| Self {
+            name,
+            superclass,
+            methods,
+            this: this.clone(),
+        })
+    }
+
+    /// Looks the method up on this class, then up the superclass chain.
+    pub fn find_method(&self, name: &str) -> Option<Rc<LoxFunction>> {
+        match self.methods.get(name) {
+            Some(method) => Some(method.clone()),
+            None => self
+                .superclass
+                .as_ref()
+                .and_then(|superclass| superclass.find_method(name)),
+        }
+    }
+}
+
+impl Trace for LoxClass {
+    fn trace(&self, references: &mut Vec<usize>) {
+        if let Some(superclass) = &self.superclass {
+            references.push(address(superclass));
+        }
+
+        references.extend(self.methods.values().map(address));
+    }
+}
+
+impl LoxCallable for LoxClass {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The arity of `init`, or no arguments without one.
+    fn arity(&self) -> Arity {
+        self.find_method("init")
+            .map_or(Arity::Fixed(0), |initializer| initializer.arity())
+    }
+
+    fn call(&self, interpreter: &Interpreter, arguments: Vec<Value>) -> Result<Value> {
+        let class = self.this.upgrade().expect("a class outlives its calls");
+        let instance = Value::Instance(Rc::new(RefCell::new(LoxInstance::new(class))));
+        interpreter.heap().track_value(&instance)?;
+
+        if let Some(initializer) = self.find_method("init") {
+            initializer
+                .bind(instance.clone(), interpreter.heap())?
+                .call(interpreter, arguments)?;
+        }
+
+        Ok(instance)
+    }
+
+    fn file(&self) -> Option<Rc<str>> {
+        self.find_method("init")
+            .and_then(|initializer| initializer.file())
+    }
+
+    fn describe(&self) -> String {
+        self.name.clone()
+    }
+}
+
+/// An instance of a Lox class. Fields are created by assigning to them.
+#[derive(Debug)]
+pub struct LoxInstance {
+    class: Rc<LoxClass>,
+    fields: HashMap<String, Value>,
+}
+
+impl LoxInstance {
+    pub fn new(class: Rc<LoxClass>) -> Self {
+        Self {
+            class,
+            fields: HashMap::new(),
+        }
+    }
+
+    pub fn class(&self) -> &Rc<LoxClass> {
+        &self.class
+    }
+
+    pub fn set(&mut self, name: &Token, value: Value) {
+        self.fields.insert(name.lexeme().to_string(), value);
+    }
+
+    pub fn field(&self, name: &str) -> Option<&Value> {
+        self.fields.get(name)
+    }
+
+    /// Sets a field the interpreter fills in itself, rather than one assigned in Lox.
+    pub fn set_field(&mut self, name: &str, value: Value) {
+        self.fields.insert(name.to_string(), value);
+    }
+
+    /// Empties the instance out, for the collector.
+    pub fn take_fields(&mut self) -> HashMap<String, Value> {
+        std::mem::take(&mut self.fields)
+    }
+}
+
+impl Trace for LoxInstance {
+    fn trace(&self, references: &mut Vec<usize>) {
+        references.push(address(&self.class));
+
+        for value in self.fields.values() {
+            value.trace(references);
+        }
+    }
+}
+
+impl HeapSize for LoxInstance {
+    fn heap_size(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|(name, value)| size_of::<(String, Value)>() + name.len() + value.heap_size())
+            .sum()
+    }
+}
+
+/// Reads a property off `instance`. Fields shadow methods, and methods come back bound to the
+/// instance.
+pub fn get_property(
+    instance: &Rc<RefCell<LoxInstance>>,
+    name: &Token,
+    heap: &Heap,
+) -> Result<Value> {
+    if let Some(value) = instance.borrow().fields.get(name.lexeme()) {
+        return Ok(value.clone());
+    }
+
+    let method = instance.borrow().class.find_method(name.lexeme());
+
+    match method {
+        Some(method) => Ok(Value::Callable(
+            method.bind(Value::Instance(instance.clone()), heap)?,
+        )),
+        None => Err(runtime_error(
+            name,
+            &format!("Undefined property '{}'.", name.lexeme()),
+        )),
+    }
+}
@@ -0,0 +1,212 @@
+use crate::interpreter::map::{Map, MapKey};
+use crate::interpreter::number::{as_float, as_integer};
+use crate::interpreter::value::Value;
+use crate::scanner::token_type::NumberType;
+use std::collections::HashMap;
+
+/// Rust values that can be handed to Lox.
+pub trait IntoValue {
+    fn into_value(self) -> Value;
+}
+
+/// Rust values that can be read back out of a Lox value. An `Err` is a message, the same as a
+/// native's, saying what was expected.
+pub trait FromValue: Sized {
+    fn from_value(value: Value) -> Result<Self, String>;
+}
+
+// the message for a value of the wrong type
+fn expected(what: &str, value: &Value) -> String {
+    format!("Expected {}, got {}.", what, value.type_name())
+}
+
+impl IntoValue for Value {
+    fn into_value(self) -> Value {
+        self
+    }
+}
+
+impl FromValue for Value {
+    fn from_value(value: Value) -> Result<Self, String> {
+        Ok(value)
+    }
+}
+
+impl IntoValue for () {
+    fn into_value(self) -> Value {
+        Value::Nil
+    }
+}
+
+impl FromValue for () {
+    fn from_value(value: Value) -> Result<Self, String> {
+        match value {
+            Value::Nil => Ok(()),
+            value => Err(expected("nil", &value)),
+        }
+    }
+}
+
+impl IntoValue for bool {
+    fn into_value(self) -> Value {
+        Value::Bool(self)
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: Value) -> Result<Self, String> {
+        match value {
+            Value::Bool(b) => Ok(b),
+            value => Err(expected("a boolean", &value)),
+        }
+    }
+}
+
+impl IntoValue for f64 {
+    fn into_value(self) -> Value {
+        Value::Number(NumberType::Float(self))
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(value: Value) -> Result<Self, String> {
+        match value {
+            Value::Number(number) => Ok(as_float(number)),
+            value => Err(expected("a number", &value)),
+        }
+    }
+}
+
+impl IntoValue for i64 {
+    fn into_value(self) -> Value {
+        Value::Number(NumberType::Integer(self))
+    }
+}
+
+// floats with nothing after the point convert too, since `/` always gives a float
+impl FromValue for i64 {
+    fn from_value(value: Value) -> Result<Self, String> {
+        match value {
+            Value::Number(number) => {
+                as_integer(number).ok_or_else(|| format!("Expected an integer, got {}.", number))
+            }
+            value => Err(expected("an integer", &value)),
+        }
+    }
+}
+
+impl IntoValue for i32 {
+    fn into_value(self) -> Value {
+        i64::from(self).into_value()
+    }
+}
+
+impl FromValue for i32 {
+    fn from_value(value: Value) -> Result<Self, String> {
+        let int = i64::from_value(value)?;
+
+        i32::try_from(int).map_err(|_| format!("{} is out of range.", int))
+    }
+}
+
+impl IntoValue for usize {
+    fn into_value(self) -> Value {
+        Value::Number(match i64::try_from(self) {
+            Ok(int) => NumberType::Integer(int),
+            Err(_) => NumberType::Float(self as f64),
+        })
+    }
+}
+
+impl FromValue for usize {
+    fn from_value(value: Value) -> Result<Self, String> {
+        let int = i64::from_value(value)?;
+
+        usize::try_from(int).map_err(|_| format!("{} is out of range.", int))
+    }
+}
+
+impl IntoValue for String {
+    fn into_value(self) -> Value {
+        Value::String(self)
+    }
+}
+
+impl IntoValue for &str {
+    fn into_value(self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: Value) -> Result<Self, String> {
+        match value {
+            Value::String(string) => Ok(string),
+            value => Err(expected("a string", &value)),
+        }
+    }
+}
+
+/// `None` is nil.
+impl<T: IntoValue> IntoValue for Option<T> {
+    fn into_value(self) -> Value {
+        match self {
+            Some(value) => value.into_value(),
+            None => Value::Nil,
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: Value) -> Result<Self, String> {
+        match value {
+            Value::Nil => Ok(None),
+            value => T::from_value(value).map(Some),
+        }
+    }
+}
+
+impl<T: IntoValue> IntoValue for Vec<T> {
+    fn into_value(self) -> Value {
+        Value::list(self.into_iter().map(IntoValue::into_value).collect())
+    }
+}
+
+/// The list is copied, so changes to the `Vec` aren't seen by Lox.
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: Value) -> Result<Self, String> {
+        match value {
+            Value::List(list) => list.borrow().iter().cloned().map(T::from_value).collect(),
+            value => Err(expected("a list", &value)),
+        }
+    }
+}
+
+impl<T: IntoValue> IntoValue for HashMap<String, T> {
+    fn into_value(self) -> Value {
+        let mut map = Map::new();
+        for (key, value) in self {
+            map.insert(MapKey::String(key), value.into_value());
+        }
+
+        Value::map(map)
+    }
+}
+
+/// Only maps with string keys convert. Like lists, the map is copied.
+impl<T: FromValue> FromValue for HashMap<String, T> {
+    fn from_value(value: Value) -> Result<Self, String> {
+        let map = match value {
+            Value::Map(map) => map,
+            value => return Err(expected("a map", &value)),
+        };
+
+        let map = map.borrow();
+        map.iter()
+            .map(|(key, value)| match key {
+                MapKey::String(key) => Ok((key.clone(), T::from_value(value.clone())?)),
+                key => Err(format!("Expected string keys, got {}.", key)),
+            })
+            .collect()
+    }
+}
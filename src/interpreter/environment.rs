@@ -0,0 +1,154 @@
+use crate::errors::Error;
+use crate::interpreter::heap::{address, HeapSize, Trace};
+use crate::interpreter::runtime_error;
+use crate::interpreter::value::Value;
+use crate::scanner::token::Token;
+use crate::Result;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::rc::Rc;
+
+/// One scope's variables, chained to the scope that encloses it. The outermost environment holds
+/// the globals.
+#[derive(Debug, Default)]
+pub struct Environment {
+    values: HashMap<String, Value>,
+    enclosing: Option<Rc<RefCell<Environment>>>,
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_enclosing(enclosing: Rc<RefCell<Environment>>) -> Self {
+        Self {
+            values: HashMap::new(),
+            enclosing: Some(enclosing),
+        }
+    }
+
+    /// The outermost scope around `environment`, which holds the globals of the file it's in.
+    pub fn globals(environment: &Rc<RefCell<Environment>>) -> Rc<RefCell<Environment>> {
+        let mut environment = environment.clone();
+
+        loop {
+            let enclosing = environment.borrow().enclosing.clone();
+
+            match enclosing {
+                Some(enclosing) => environment = enclosing,
+                None => return environment,
+            }
+        }
+    }
+
+    /// Declares `name` in this scope. A global can be declared again, which replaces it, so a
+    /// REPL session can redefine things. Declaring a local twice in the same scope is an error.
+    pub fn define(&mut self, name: &Token, value: Value) -> Result<()> {
+        if self.enclosing.is_some() && self.values.contains_key(name.lexeme()) {
+            return Err(runtime_error(
+                name,
+                &format!(
+                    "Already a variable named '{}' in this scope.",
+                    name.lexeme()
+                ),
+            ));
+        }
+
+        self.values.insert(name.lexeme().to_string(), value);
+
+        Ok(())
+    }
+
+    /// Binds a name the interpreter introduces itself, such as `this` and `super`, which can't
+    /// clash with a declaration.
+    pub fn bind(&mut self, name: &str, value: Value) {
+        self.values.insert(name.to_string(), value);
+    }
+
+    pub fn get(&self, name: &Token) -> Result<Value> {
+        if let Some(value) = self.values.get(name.lexeme()) {
+            return Ok(value.clone());
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow().get(name),
+            None => Err(undefined(name)),
+        }
+    }
+
+    /// Reads `name` from the scope `distance` steps out, where the resolver found it.
+    pub fn get_at(&self, distance: usize, name: &Token) -> Result<Value> {
+        self.lookup_at(distance, name.lexeme())
+            .ok_or_else(|| undefined(name))
+    }
+
+    /// Like `get_at`, for names the interpreter binds itself, such as `this`.
+    pub fn lookup_at(&self, distance: usize, name: &str) -> Option<Value> {
+        if distance == 0 {
+            return self.values.get(name).cloned();
+        }
+
+        self.enclosing
+            .as_ref()
+            .and_then(|enclosing| enclosing.borrow().lookup_at(distance - 1, name))
+    }
+
+    pub fn assign_at(&mut self, distance: usize, name: &Token, value: Value) -> Result<()> {
+        if distance == 0 {
+            return match self.values.get_mut(name.lexeme()) {
+                Some(slot) => {
+                    *slot = value;
+
+                    Ok(())
+                }
+                None => Err(undefined(name)),
+            };
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow_mut().assign_at(distance - 1, name, value),
+            None => Err(undefined(name)),
+        }
+    }
+
+    /// Assigns to the nearest scope that declares `name`. Assigning never declares a variable.
+    pub fn assign(&mut self, name: &Token, value: Value) -> Result<()> {
+        if let Some(slot) = self.values.get_mut(name.lexeme()) {
+            *slot = value;
+
+            return Ok(());
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow_mut().assign(name, value),
+            None => Err(undefined(name)),
+        }
+    }
+}
+
+impl HeapSize for Environment {
+    fn heap_size(&self) -> usize {
+        self.values
+            .iter()
+            .map(|(name, value)| size_of::<(String, Value)>() + name.len() + value.heap_size())
+            .sum()
+    }
+}
+
+impl Trace for Environment {
+    fn trace(&self, references: &mut Vec<usize>) {
+        for value in self.values.values() {
+            value.trace(references);
+        }
+
+        if let Some(enclosing) = &self.enclosing {
+            references.push(address(enclosing));
+        }
+    }
+}
+
+fn undefined(name: &Token) -> Error {
+    runtime_error(name, &format!("Undefined variable '{}'.", name.lexeme()))
+}
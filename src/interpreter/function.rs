@@ -0,0 +1,124 @@
+use crate::interpreter::callable::{Arity, LoxCallable};
+use crate::interpreter::environment::Environment;
+use crate::interpreter::heap::{address, Heap, Trace};
+use crate::interpreter::interpreter::{Flow, Interpreter};
+use crate::interpreter::value::Value;
+use crate::scanner::token::Token;
+use crate::stmt::Stmt;
+use crate::Result;
+use std::cell::RefCell;
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+
+/// A function declared in Lox, named or anonymous. It keeps the scope it was declared in alive,
+/// so its body sees the variables around it even after that scope has been left.
+pub struct LoxFunction {
+    name: Option<String>,
+    params: Vec<Token>,
+    body: Rc<Vec<Stmt>>,
+    closure: Rc<RefCell<Environment>>,
+    // an `init` method, which always returns its instance
+    is_initializer: bool,
+    file: Rc<str>,
+}
+
+impl LoxFunction {
+    pub fn new(
+        name: Option<String>,
+        params: Vec<Token>,
+        body: Rc<Vec<Stmt>>,
+        closure: Rc<RefCell<Environment>>,
+        file: Rc<str>,
+    ) -> Self {
+        Self {
+            name,
+            params,
+            body,
+            closure,
+            is_initializer: false,
+            file,
+        }
+    }
+
+    pub fn method(
+        name: &Token,
+        params: Vec<Token>,
+        body: Rc<Vec<Stmt>>,
+        closure: Rc<RefCell<Environment>>,
+        file: Rc<str>,
+    ) -> Self {
+        Self {
+            is_initializer: name.lexeme() == "init",
+            ..Self::new(Some(name.lexeme().to_string()), params, body, closure, file)
+        }
+    }
+
+    /// The method with `this` bound to `instance`, as read off that instance.
+    pub fn bind(&self, instance: Value, heap: &Heap) -> Result<Rc<LoxFunction>> {
+        let mut scope = Environment::with_enclosing(self.closure.clone());
+        scope.bind("this", instance);
+
+        let closure = Rc::new(RefCell::new(scope));
+        heap.track_environment(&closure)?;
+
+        let method = Rc::new(Self {
+            name: self.name.clone(),
+            params: self.params.clone(),
+            body: self.body.clone(),
+            closure,
+            is_initializer: self.is_initializer,
+            file: self.file.clone(),
+        });
+        heap.track_function(&method)?;
+
+        Ok(method)
+    }
+}
+
+impl Trace for LoxFunction {
+    fn trace(&self, references: &mut Vec<usize>) {
+        references.push(address(&self.closure));
+    }
+}
+
+// the closure is left out, it can refer back to the function itself
+impl Debug for LoxFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoxFunction")
+            .field("name", &self.name)
+            .field("params", &self.params)
+            .finish()
+    }
+}
+
+impl LoxCallable for LoxFunction {
+    fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or("")
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::Fixed(self.params.len())
+    }
+
+    fn file(&self) -> Option<Rc<str>> {
+        Some(self.file.clone())
+    }
+
+    fn call(&self, interpreter: &Interpreter, arguments: Vec<Value>) -> Result<Value> {
+        let mut scope = Environment::with_enclosing(self.closure.clone());
+        for (param, argument) in self.params.iter().zip(arguments) {
+            scope.define(param, argument)?;
+        }
+
+        let value = match interpreter.execute_block(&self.body, scope)? {
+            Flow::Return(value) => value,
+            _ => Value::Nil,
+        };
+
+        if self.is_initializer {
+            return Ok(self.closure.borrow().lookup_at(0, "this").unwrap_or(value));
+        }
+
+        Ok(value)
+    }
+}
@@ -0,0 +1,465 @@
+//! Accounting for the memory scripts allocate, and collection of the objects only cycles keep
+//! alive.
+//!
+//! Every list, map, instance, native object, environment, function and class is registered with the heap when
+//! it's made, or when Rust code hands it to a script, and allocations are charged against it as they happen. Nothing is credited back
+//! when an object is freed, so once the charges add up to more than the limit the heap collects
+//! cycles, measures what's still alive and starts counting again from there. Only if the live
+//! objects alone go over the limit does the allocation fail. Strings are measured as part of
+//! whatever holds them.
+//!
+//! Objects are reference counted, which frees everything but cycles, like a closure stored on an
+//! object it captures. To find those, the collector counts how many of each object's references
+//! come from other registered objects. An object with more references than that is held from
+//! outside, by a variable in scope or a value the interpreter is working with, and so is
+//! everything it refers to. The rest can only be reached through each other, and are emptied
+//! out, which breaks the cycles and lets reference counting free them.
+
+use crate::errors::{Error, ErrorType, LimitExceeded};
+use crate::interpreter::class::{LoxClass, LoxInstance};
+use crate::interpreter::environment::Environment;
+use crate::interpreter::function::LoxFunction;
+use crate::interpreter::list::List;
+use crate::interpreter::map::Map;
+use crate::interpreter::native_class::NativeObject;
+use crate::interpreter::value::Value;
+use crate::Result;
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::mem::{size_of, take};
+use std::rc::{Rc, Weak};
+
+/// How many bytes something takes up, not counting the objects it refers to, which are measured
+/// on their own.
+pub trait HeapSize {
+    fn heap_size(&self) -> usize;
+}
+
+/// Lists the objects something holds a reference to, by `address`.
+pub trait Trace {
+    fn trace(&self, references: &mut Vec<usize>);
+}
+
+impl HeapSize for Value {
+    fn heap_size(&self) -> usize {
+        match self {
+            Value::String(string) => string.len(),
+            _ => 0,
+        }
+    }
+}
+
+impl Trace for Value {
+    fn trace(&self, references: &mut Vec<usize>) {
+        match self {
+            Value::List(list) => references.push(address(list)),
+            Value::Map(map) => references.push(address(map)),
+            Value::Callable(callable) => references.push(address(callable)),
+            Value::Class(class) => references.push(address(class)),
+            Value::Instance(instance) => references.push(address(instance)),
+            Value::Native(object) => references.push(address(object)),
+            // modules stay loaded, so what they refer to is never garbage
+            Value::Nil
+            | Value::Bool(..)
+            | Value::Number(..)
+            | Value::String(..)
+            | Value::Module(..) => {}
+        }
+    }
+}
+
+/// Identifies an object by where it lives, whatever type the `Rc` pointing at it has.
+pub fn address<T: ?Sized>(object: &Rc<T>) -> usize {
+    Rc::as_ptr(object) as *const () as usize
+}
+
+// an object the heap keeps track of, without keeping it alive
+enum Object {
+    Environment(Weak<RefCell<Environment>>),
+    List(Weak<RefCell<List<Value>>>),
+    Map(Weak<RefCell<Map<Value>>>),
+    Instance(Weak<RefCell<LoxInstance>>),
+    Native(Weak<NativeObject>),
+    Function(Weak<LoxFunction>),
+    Class(Weak<LoxClass>),
+}
+
+// an object held on to while it's measured or collected
+enum Live {
+    Environment(Rc<RefCell<Environment>>),
+    List(Rc<RefCell<List<Value>>>),
+    Map(Rc<RefCell<Map<Value>>>),
+    Instance(Rc<RefCell<LoxInstance>>),
+    Native(Rc<NativeObject>),
+    Function(Rc<LoxFunction>),
+    Class(Rc<LoxClass>),
+}
+
+impl Object {
+    // where the object lives, which stays taken while the heap points at it, even once it's freed
+    fn address(&self) -> usize {
+        fn address<T: ?Sized>(object: &Weak<T>) -> usize {
+            Weak::as_ptr(object) as *const () as usize
+        }
+
+        match self {
+            Object::Environment(environment) => address(environment),
+            Object::List(list) => address(list),
+            Object::Map(map) => address(map),
+            Object::Instance(instance) => address(instance),
+            Object::Native(object) => address(object),
+            Object::Function(function) => address(function),
+            Object::Class(class) => address(class),
+        }
+    }
+
+    // `None` once the object has been freed
+    fn upgrade(&self) -> Option<Live> {
+        Some(match self {
+            Object::Environment(environment) => Live::Environment(environment.upgrade()?),
+            Object::List(list) => Live::List(list.upgrade()?),
+            Object::Map(map) => Live::Map(map.upgrade()?),
+            Object::Instance(instance) => Live::Instance(instance.upgrade()?),
+            Object::Native(object) => Live::Native(object.upgrade()?),
+            Object::Function(function) => Live::Function(function.upgrade()?),
+            Object::Class(class) => Live::Class(class.upgrade()?),
+        })
+    }
+}
+
+impl Live {
+    fn address(&self) -> usize {
+        match self {
+            Live::Environment(environment) => address(environment),
+            Live::List(list) => address(list),
+            Live::Map(map) => address(map),
+            Live::Instance(instance) => address(instance),
+            Live::Native(object) => address(object),
+            Live::Function(function) => address(function),
+            Live::Class(class) => address(class),
+        }
+    }
+
+    // the references to the object, other than this one
+    fn references(&self) -> usize {
+        let count = match self {
+            Live::Environment(environment) => Rc::strong_count(environment),
+            Live::List(list) => Rc::strong_count(list),
+            Live::Map(map) => Rc::strong_count(map),
+            Live::Instance(instance) => Rc::strong_count(instance),
+            Live::Native(object) => Rc::strong_count(object),
+            Live::Function(function) => Rc::strong_count(function),
+            Live::Class(class) => Rc::strong_count(class),
+        };
+
+        count - 1
+    }
+
+    // what the object refers to. `None` when it's being changed right now and can't be looked
+    // into
+    fn trace(&self) -> Option<Vec<usize>> {
+        fn borrowed<T: Trace>(object: &RefCell<T>, references: &mut Vec<usize>) -> Option<()> {
+            object.try_borrow().ok()?.trace(references);
+            Some(())
+        }
+
+        let mut references = vec![];
+
+        match self {
+            Live::Environment(environment) => borrowed(environment, &mut references)?,
+            Live::List(list) => borrowed(list, &mut references)?,
+            Live::Map(map) => borrowed(map, &mut references)?,
+            Live::Instance(instance) => borrowed(instance, &mut references)?,
+            // the Rust value can't be looked into, so whatever it holds counts as in use
+            Live::Native(..) => {}
+            Live::Function(function) => function.trace(&mut references),
+            Live::Class(class) => class.trace(&mut references),
+        }
+
+        Some(references)
+    }
+
+    // an object that's being changed right now is counted as empty
+    fn size(&self) -> usize {
+        fn borrowed<T: HeapSize>(object: &RefCell<T>) -> usize {
+            size_of::<T>() + object.try_borrow().map_or(0, |object| object.heap_size())
+        }
+
+        match self {
+            Live::Environment(environment) => borrowed(environment),
+            Live::List(list) => borrowed(list),
+            Live::Map(map) => borrowed(map),
+            Live::Instance(instance) => borrowed(instance),
+            Live::Native(..) => size_of::<NativeObject>(),
+            Live::Function(..) => size_of::<LoxFunction>(),
+            Live::Class(..) => size_of::<LoxClass>(),
+        }
+    }
+
+    // takes out everything the object refers to, to be dropped once nothing is borrowed. Only
+    // the objects scripts can change are emptied, and every cycle goes through one of them
+    fn empty(&self) -> Option<Box<dyn Any>> {
+        fn emptied<T: Default + 'static>(object: &RefCell<T>) -> Option<Box<dyn Any>> {
+            let mut object = object.try_borrow_mut().ok()?;
+            Some(Box::new(take(&mut *object)))
+        }
+
+        match self {
+            Live::Environment(environment) => emptied(environment),
+            Live::List(list) => emptied(list),
+            Live::Map(map) => emptied(map),
+            Live::Instance(instance) => {
+                let mut instance = instance.try_borrow_mut().ok()?;
+                Some(Box::new(instance.take_fields()))
+            }
+            Live::Native(..) | Live::Function(..) | Live::Class(..) => None,
+        }
+    }
+}
+
+/// A snapshot of the heap.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HeapStats {
+    pub live_objects: usize,
+    pub bytes: usize,
+    /// How many times cycles have been collected.
+    pub collections: usize,
+}
+
+#[derive(Default)]
+pub struct Heap {
+    objects: RefCell<Vec<Object>>,
+    // how many objects were alive when they were last measured
+    measured_objects: Cell<usize>,
+    // the bytes the live objects took up when they were last measured
+    live: Cell<usize>,
+    // bytes charged since then
+    allocated: Cell<usize>,
+    limit: Cell<Option<usize>>,
+    collections: Cell<usize>,
+}
+
+impl Heap {
+    pub fn set_limit(&self, limit: Option<usize>) {
+        self.limit.set(limit);
+    }
+
+    /// The bytes taken up by the objects that are alive.
+    pub fn usage(&self) -> usize {
+        self.measure();
+        self.live.get()
+    }
+
+    pub fn stats(&self) -> HeapStats {
+        self.measure();
+
+        HeapStats {
+            live_objects: self.measured_objects.get(),
+            bytes: self.live.get(),
+            collections: self.collections.get(),
+        }
+    }
+
+    /// Registers a new environment and charges for it.
+    pub fn track_environment(&self, environment: &Rc<RefCell<Environment>>) -> Result<()> {
+        self.track(
+            Object::Environment(Rc::downgrade(environment)),
+            size_of::<Environment>() + environment.borrow().heap_size(),
+        )
+    }
+
+    pub fn track_function(&self, function: &Rc<LoxFunction>) -> Result<()> {
+        self.track(
+            Object::Function(Rc::downgrade(function)),
+            size_of::<LoxFunction>(),
+        )
+    }
+
+    pub fn track_class(&self, class: &Rc<LoxClass>) -> Result<()> {
+        self.track(Object::Class(Rc::downgrade(class)), size_of::<LoxClass>())
+    }
+
+    pub fn track_native(&self, object: &Rc<NativeObject>) -> Result<()> {
+        self.track(
+            Object::Native(Rc::downgrade(object)),
+            size_of::<NativeObject>(),
+        )
+    }
+
+    /// Registers a new list, map or instance and charges for it. Other values aren't objects the
+    /// heap keeps track of and are only charged for.
+    pub fn track_value(&self, value: &Value) -> Result<()> {
+        match value {
+            Value::List(list) => self.track(
+                Object::List(Rc::downgrade(list)),
+                size_of::<List<Value>>() + list.borrow().heap_size(),
+            ),
+            Value::Map(map) => self.track(
+                Object::Map(Rc::downgrade(map)),
+                size_of::<Map<Value>>() + map.borrow().heap_size(),
+            ),
+            Value::Instance(instance) => self.track(
+                Object::Instance(Rc::downgrade(instance)),
+                size_of::<LoxInstance>() + instance.borrow().heap_size(),
+            ),
+            value => self.allocate(value.heap_size()),
+        }
+    }
+
+    /// Registers the lists, maps and instances in a value made by Rust code, and the ones they
+    /// hold, and charges for them. The objects the heap already knows about are left alone.
+    pub fn adopt(&self, value: &Value) -> Result<()> {
+        let mut tracked: HashSet<usize> =
+            self.objects.borrow().iter().map(Object::address).collect();
+        let mut pending = vec![value.clone()];
+
+        while let Some(value) = pending.pop() {
+            let object = match &value {
+                Value::List(list) => address(list),
+                Value::Map(map) => address(map),
+                Value::Instance(instance) => address(instance),
+                _ => continue,
+            };
+
+            if !tracked.insert(object) {
+                continue;
+            }
+
+            match &value {
+                Value::List(list) => pending.extend(list.borrow().iter().cloned()),
+                Value::Map(map) => {
+                    pending.extend(map.borrow().iter().map(|(_, value)| value.clone()))
+                }
+                _ => {}
+            }
+
+            self.track_value(&value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Charges for holding `value` in a variable, field or entry.
+    pub fn store(&self, value: &Value) -> Result<()> {
+        self.allocate(size_of::<Value>() + value.heap_size())
+    }
+
+    /// Charges for `bytes` more memory, failing when the objects alive and the new allocation
+    /// don't fit in the limit together.
+    pub fn allocate(&self, bytes: usize) -> Result<()> {
+        self.allocated.set(self.allocated.get() + bytes);
+
+        let Some(limit) = self.limit.get() else {
+            return Ok(());
+        };
+
+        if self.live.get() + self.allocated.get() <= limit {
+            return Ok(());
+        }
+
+        // the new allocation isn't necessarily held by anything that gets measured yet
+        self.collect();
+        if self.live.get() + bytes > limit {
+            return Err(Error(ErrorType::Limit(LimitExceeded::Memory(limit))));
+        }
+
+        Ok(())
+    }
+
+    /// Frees the objects only cycles keep alive, and returns how many there were.
+    pub fn collect(&self) -> usize {
+        let objects: Vec<Live> = self
+            .objects
+            .borrow()
+            .iter()
+            .filter_map(Object::upgrade)
+            .collect();
+
+        let positions: HashMap<usize, usize> = objects
+            .iter()
+            .enumerate()
+            .map(|(position, object)| (object.address(), position))
+            .collect();
+
+        let traced: Vec<Option<Vec<usize>>> = objects
+            .iter()
+            .map(|object| {
+                object.trace().map(|references| {
+                    references
+                        .iter()
+                        .filter_map(|address| positions.get(address).copied())
+                        .collect()
+                })
+            })
+            .collect();
+
+        let mut from_outside: Vec<usize> = objects.iter().map(Live::references).collect();
+        for position in traced.iter().flatten().flatten() {
+            from_outside[*position] -= 1;
+        }
+
+        // an object that couldn't be looked into is in use, and its references count as from
+        // outside
+        let mut pending: Vec<usize> = (0..objects.len())
+            .filter(|position| from_outside[*position] > 0 || traced[*position].is_none())
+            .collect();
+        let mut reachable = vec![false; objects.len()];
+
+        while let Some(position) = pending.pop() {
+            if !reachable[position] {
+                reachable[position] = true;
+                pending.extend(traced[position].iter().flatten());
+            }
+        }
+
+        let contents: Vec<Box<dyn Any>> = objects
+            .iter()
+            .zip(&reachable)
+            .filter(|(_, reachable)| !**reachable)
+            .filter_map(|(object, _)| object.empty())
+            .collect();
+        let freed = reachable.iter().filter(|reachable| !**reachable).count();
+
+        drop(contents);
+        drop(objects);
+
+        self.collections.set(self.collections.get() + 1);
+        self.measure();
+
+        freed
+    }
+
+    // charged for before it's registered, so a measurement the charge sets off doesn't count it
+    // twice
+    fn track(&self, object: Object, bytes: usize) -> Result<()> {
+        self.allocate(bytes)?;
+        self.objects.borrow_mut().push(object);
+
+        // without a limit nothing else collects cycles or forgets the freed objects
+        if self.objects.borrow().len() > 2 * self.measured_objects.get().max(1024) {
+            self.collect();
+        }
+
+        Ok(())
+    }
+
+    // forgets the objects that have been freed and totals up the rest
+    fn measure(&self) {
+        let mut live = 0;
+
+        self.objects
+            .borrow_mut()
+            .retain(|object| match object.upgrade() {
+                Some(object) => {
+                    live += object.size();
+                    true
+                }
+                None => false,
+            });
+
+        self.measured_objects.set(self.objects.borrow().len());
+        self.live.set(live);
+        self.allocated.set(0);
+    }
+}
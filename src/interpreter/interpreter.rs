@@ -0,0 +1,1106 @@
+use crate::errors::{Error, ErrorType, RuntimeError, TraceFrame};
+use crate::expr::{Binary, Call, Expr, Index, Logical, SetIndex, Super, Unary};
+use crate::interpreter::callable::{Arity, LoxCallable};
+use crate::interpreter::class::{get_property, LoxClass, LoxInstance};
+use crate::interpreter::environment::Environment;
+use crate::interpreter::function::LoxFunction;
+use crate::interpreter::heap::Heap;
+use crate::interpreter::limits::{Budget, Limits};
+use crate::interpreter::map::{Map, MapKey};
+use crate::interpreter::module::Module;
+use crate::interpreter::native;
+use crate::interpreter::native::NativeFunction;
+use crate::interpreter::native_class::NativeClass;
+use crate::interpreter::number;
+use crate::interpreter::runtime_error;
+use crate::interpreter::value::Value;
+use crate::parser::parser::Parser;
+use crate::resolver::resolver::Resolver;
+use crate::scanner::scanner::Scanner;
+use crate::scanner::token::Token;
+use crate::scanner::token_type::{Keyword, NumberType, SingleCharacter, SingleOrDouble, TokenType};
+use crate::stmt::{Class, If, Import, Stmt, Throw, Try, While};
+use crate::Result;
+use std::any::{type_name, Any, TypeId};
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Walks the syntax tree and evaluates it. Globals live as long as the interpreter, so code run
+/// through it piece by piece, like REPL input, shares them.
+pub struct Interpreter {
+    globals: Rc<RefCell<Environment>>,
+    // the innermost scope of the code being run
+    environment: RefCell<Rc<RefCell<Environment>>>,
+    // each call in progress, innermost last
+    frames: RefCell<Vec<Frame>>,
+    // the files being run, named in stack traces. The script comes first, followed by the
+    // modules being imported, innermost last
+    files: RefCell<Vec<Rc<str>>>,
+    // the natives, which every module's globals start out with
+    natives: RefCell<HashMap<String, Value>>,
+    // the classes implemented in Rust, by the type of their objects
+    native_classes: RefCell<HashMap<TypeId, Rc<NativeClass>>>,
+    // each module imported, by its canonical path
+    modules: RefCell<HashMap<PathBuf, Rc<Module>>>,
+    // how many calls can be in progress at once before the script is stopped
+    max_call_depth: Cell<usize>,
+    budget: Budget,
+    // whether a run is in progress, which any runs started inside it are part of
+    running: Cell<bool>,
+    heap: Heap,
+    // the value of the `throw` being unwound, until it's caught
+    thrown: RefCell<Option<Value>>,
+    // the class of the objects runtime errors are caught as
+    error_class: Rc<LoxClass>,
+    // told about each of the resolver's warnings, for the script and the modules it imports
+    on_warning: RefCell<Box<WarningHandler>>,
+}
+
+type WarningHandler = dyn Fn(&str);
+
+/// The default call depth limit. Every Lox call takes several Rust frames, more than a small
+/// thread stack has room for, so calls run on stack that's added as it runs low.
+pub const MAX_CALL_DEPTH: usize = 1000;
+
+// how much stack a call needs left to start on the stack it's on, and how much is added when it
+// doesn't have that much. A call runs a whole function body before the next call checks again,
+// so the first leaves room for deeply nested expressions as well
+const STACK_RED_ZONE: usize = 1024 * 1024;
+const STACK_GROWTH: usize = 8 * 1024 * 1024;
+
+struct Frame {
+    function: String,
+    // where the function is written, `None` for natives, which traces leave out
+    file: Option<Rc<str>>,
+    // the closing parenthesis of the call, in the caller. `None` for calls made from Rust
+    call_site: Option<Token>,
+}
+
+/// How a statement finished. Anything but `Next` unwinds the enclosing blocks up to the loop or
+/// function call that handles it.
+pub enum Flow {
+    Next,
+    Break,
+    Continue,
+    Return(Value),
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        let globals = Rc::new(RefCell::new(Environment::new()));
+
+        let interpreter = Self {
+            environment: RefCell::new(globals.clone()),
+            globals,
+            frames: RefCell::new(vec![]),
+            files: RefCell::new(vec![Rc::from("<stdin>")]),
+            natives: RefCell::new(HashMap::new()),
+            native_classes: RefCell::new(HashMap::new()),
+            modules: RefCell::new(HashMap::new()),
+            max_call_depth: Cell::new(MAX_CALL_DEPTH),
+            budget: Budget::default(),
+            running: Cell::new(false),
+            heap: Heap::default(),
+            thrown: RefCell::new(None),
+            error_class: LoxClass::new(String::from("Error"), None, HashMap::new()),
+            on_warning: RefCell::new(Box::new(|_| {})),
+        };
+
+        interpreter
+            .heap
+            .track_environment(&interpreter.globals)
+            .expect("there's no memory limit yet");
+
+        for native in native::core() {
+            interpreter.define_native(native);
+        }
+
+        interpreter
+    }
+
+    /// Makes `native` callable from Lox as a global, replacing any global of the same name.
+    pub fn define_native(&self, native: NativeFunction) {
+        let name = native.name().to_string();
+        let value = Value::Callable(Rc::new(native));
+
+        self.globals.borrow_mut().bind(&name, value.clone());
+        self.natives.borrow_mut().insert(name, value);
+    }
+
+    /// Makes `class` available to Lox as a global, and to `wrap` for values of its type.
+    pub fn define_class(&self, class: NativeClass) {
+        let class = Rc::new(class);
+        let name = class.name().to_string();
+        let value = Value::Callable(class.clone());
+
+        self.globals.borrow_mut().bind(&name, value.clone());
+        self.natives.borrow_mut().insert(name, value);
+        self.native_classes
+            .borrow_mut()
+            .insert(class.object_type(), class);
+    }
+
+    /// Hands `data` to Lox as an object of the native class defined for its type.
+    pub fn wrap<T: Any>(&self, data: T) -> Result<Value> {
+        let class = self
+            .native_classes
+            .borrow()
+            .get(&TypeId::of::<T>())
+            .cloned()
+            .ok_or_else(|| {
+                Error(ErrorType::Interpreter(RuntimeError::new(&format!(
+                    "No class is defined for {}.",
+                    type_name::<T>()
+                ))))
+            })?;
+
+        class.instantiate(self, Box::new(data))
+    }
+
+    /// A runtime error located at the call currently being run, for callables that don't have
+    /// a location of their own.
+    pub fn error_at_call(&self, message: &str) -> Error {
+        match self.frames.borrow().last() {
+            Some(Frame {
+                call_site: Some(call_site),
+                ..
+            }) => runtime_error(call_site, message),
+            _ => Error(ErrorType::Interpreter(RuntimeError::new(message))),
+        }
+    }
+
+    /// Limits how deeply calls can nest. Going deeper raises a "Stack overflow." runtime error
+    /// rather than overflowing the host stack, as long as the host stack is big enough for the
+    /// limit.
+    pub fn set_max_call_depth(&self, depth: usize) {
+        self.max_call_depth.set(depth);
+    }
+
+    /// Caps the steps and time each later run can take, and the memory they can use between
+    /// them. Going over stops the run with a `Limit` error, and leaves the interpreter ready for
+    /// the next one.
+    pub fn set_limits(&self, limits: Limits) {
+        self.budget.set_limits(limits);
+        self.heap.set_limit(limits.max_memory);
+    }
+
+    pub fn heap(&self) -> &Heap {
+        &self.heap
+    }
+
+    /// The bytes taken up by the lists, maps, instances and scopes that are still alive.
+    pub fn memory_usage(&self) -> usize {
+        self.heap.usage()
+    }
+
+    /// Names the script code is run from from now on, for stack traces.
+    pub fn set_file(&self, file: &str) {
+        self.files.replace(vec![Rc::from(file)]);
+    }
+
+    /// The file the code being run is in, the script or a module it imports.
+    pub fn file(&self) -> Rc<str> {
+        let files = self.files.borrow();
+
+        files[files.len() - 1].clone()
+    }
+
+    /// Handles the resolver's warnings, which are dropped until this is set. They don't stop the
+    /// code from running.
+    pub fn on_warning(&self, handler: impl Fn(&str) + 'static) {
+        *self.on_warning.borrow_mut() = Box::new(handler);
+    }
+
+    pub fn warn(&self, warning: &str) {
+        (self.on_warning.borrow())(warning);
+    }
+
+    /// The value of a global of the script, if it has been defined.
+    pub fn global(&self, name: &str) -> Option<Value> {
+        self.globals.borrow().lookup_at(0, name)
+    }
+
+    /// Defines a global of the script, replacing any with the same name.
+    pub fn set_global(&self, name: &str, value: Value) -> Result<()> {
+        self.heap.adopt(&value)?;
+        self.heap.store(&value)?;
+        self.globals.borrow_mut().bind(name, value);
+
+        Ok(())
+    }
+
+    pub fn interpret(&self, statements: &[Stmt]) -> Result<()> {
+        self.run(|| {
+            for statement in statements {
+                self.execute(statement)?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Evaluates an expression at the top level of the script, as a run of its own.
+    pub fn interpret_expression(&self, expr: &Expr) -> Result<Value> {
+        self.run(|| self.evaluate(expr))
+    }
+
+    /// Does `run` as one run from the top of the script, which gets the whole allowance of steps
+    /// and time. Runs started inside it, such as each statement of a snippet, share its
+    /// allowance rather than starting over. An error that makes it out has been given its trace,
+    /// and a thrown value can't be caught anymore.
+    pub fn run<T>(&self, run: impl FnOnce() -> Result<T>) -> Result<T> {
+        if self.running.replace(true) {
+            return run();
+        }
+
+        self.budget.start();
+
+        let result = run().map_err(|err| {
+            self.thrown.take();
+            self.traced(err)
+        });
+        self.running.set(false);
+
+        result
+    }
+
+    // gives a runtime error the trace of the calls in progress, unless it already has one from
+    // a call it has left
+    fn traced(&self, err: Error) -> Error {
+        match err {
+            Error(ErrorType::Interpreter(mut error)) if error.trace.is_empty() => {
+                error.trace = self.stack_trace(error.line);
+                Error(ErrorType::Interpreter(error))
+            }
+            err => err,
+        }
+    }
+
+    /// The calls in progress, innermost first. `line` is where the innermost one is at.
+    pub fn stack_trace(&self, line: Option<usize>) -> Vec<TraceFrame> {
+        let frames = self.frames.borrow();
+
+        let mut line = line
+            .or_else(|| {
+                frames
+                    .last()
+                    .and_then(|frame| frame.call_site.as_ref())
+                    .map(Token::line)
+            })
+            .unwrap_or_default();
+        let mut trace = vec![];
+
+        for frame in frames.iter().rev() {
+            if let Some(file) = &frame.file {
+                trace.push(TraceFrame {
+                    function: frame.function.clone(),
+                    file: file.to_string(),
+                    line,
+                });
+            }
+
+            if let Some(call_site) = &frame.call_site {
+                line = call_site.line();
+            }
+        }
+
+        // a call from Rust with no script running under it
+        let from_host = frames
+            .first()
+            .is_some_and(|frame| frame.call_site.is_none());
+        if !from_host {
+            trace.push(TraceFrame {
+                function: String::from("<script>"),
+                file: self.files.borrow()[0].to_string(),
+                line,
+            });
+        }
+
+        trace
+    }
+
+    fn execute(&self, statement: &Stmt) -> Result<Flow> {
+        self.budget.step()?;
+
+        match statement {
+            Stmt::Expression(expression) => {
+                self.evaluate(&expression.expression)?;
+            }
+            Stmt::Print(print) => println!("{}", self.evaluate(&print.expression)?),
+            Stmt::If(if_stmt) => return self.execute_if(if_stmt),
+            Stmt::While(while_stmt) => return self.execute_while(while_stmt),
+            Stmt::Break(..) => return Ok(Flow::Break),
+            Stmt::Continue(..) => return Ok(Flow::Continue),
+            Stmt::Var(var) => {
+                let value = match &var.initializer {
+                    Some(initializer) => self.evaluate(initializer)?,
+                    None => Value::Nil,
+                };
+
+                self.heap.store(&value)?;
+                self.environment
+                    .borrow()
+                    .borrow_mut()
+                    .define(&var.name, value)?;
+            }
+            Stmt::Block(block) => {
+                let scope = Environment::with_enclosing(self.environment.borrow().clone());
+
+                return self.execute_block(&block.statements, scope);
+            }
+            Stmt::Function(function) => {
+                let value = Rc::new(LoxFunction::new(
+                    Some(function.name.lexeme().to_string()),
+                    function.params.clone(),
+                    function.body.clone(),
+                    self.environment.borrow().clone(),
+                    self.file(),
+                ));
+                self.heap.track_function(&value)?;
+
+                self.environment
+                    .borrow()
+                    .borrow_mut()
+                    .define(&function.name, Value::Callable(value))?;
+            }
+            Stmt::Return(return_stmt) => {
+                let value = match &return_stmt.value {
+                    Some(value) => self.evaluate(value)?,
+                    None => Value::Nil,
+                };
+
+                return Ok(Flow::Return(value));
+            }
+            Stmt::Class(class) => self.execute_class(class)?,
+            Stmt::Import(import) => {
+                let module = self.import(import)?;
+
+                self.environment
+                    .borrow()
+                    .borrow_mut()
+                    .define(&import.name, Value::Module(module))?;
+            }
+            Stmt::Throw(throw) => return Err(self.throw(throw)?),
+            Stmt::Try(try_stmt) => return self.execute_try(try_stmt),
+            Stmt::Error(error) => {
+                return Err(Error(ErrorType::Interpreter(RuntimeError::new(&format!(
+                    "Can't run code with syntax errors: {}",
+                    error.message
+                )))))
+            }
+        }
+
+        Ok(Flow::Next)
+    }
+
+    // runs `statements` in `scope`, restoring the current scope afterwards even when they fail.
+    // A `break` or `continue` ends the block early and is passed on to the enclosing loop
+    pub fn execute_block(&self, statements: &[Stmt], scope: Environment) -> Result<Flow> {
+        let scope = Rc::new(RefCell::new(scope));
+        self.heap.track_environment(&scope)?;
+
+        let previous = self.environment.replace(scope);
+
+        let result = self.execute_all(statements);
+        self.environment.replace(previous);
+
+        result
+    }
+
+    fn execute_all(&self, statements: &[Stmt]) -> Result<Flow> {
+        for statement in statements {
+            match self.execute(statement)? {
+                Flow::Next => {}
+                flow => return Ok(flow),
+            }
+        }
+
+        Ok(Flow::Next)
+    }
+
+    // methods close over a scope binding `super` when there's a superclass, which sits between
+    // them and the scope the class is declared in
+    fn execute_class(&self, class: &Class) -> Result<()> {
+        let superclass = match &class.superclass {
+            Some(superclass) => match self.look_up(&superclass.name, &superclass.depth)? {
+                Value::Class(superclass) => Some(superclass),
+                _ => {
+                    return Err(runtime_error(
+                        &superclass.name,
+                        "Superclass must be a class.",
+                    ))
+                }
+            },
+            None => None,
+        };
+
+        let mut closure = self.environment.borrow().clone();
+        if let Some(superclass) = &superclass {
+            let mut scope = Environment::with_enclosing(closure);
+            scope.bind("super", Value::Class(superclass.clone()));
+            closure = Rc::new(RefCell::new(scope));
+            self.heap.track_environment(&closure)?;
+        }
+
+        let methods = class
+            .methods
+            .iter()
+            .map(|method| {
+                let function = Rc::new(LoxFunction::method(
+                    &method.name,
+                    method.params.clone(),
+                    method.body.clone(),
+                    closure.clone(),
+                    self.file(),
+                ));
+                self.heap.track_function(&function)?;
+
+                Ok((method.name.lexeme().to_string(), function))
+            })
+            .collect::<Result<_>>()?;
+
+        let value = LoxClass::new(class.name.lexeme().to_string(), superclass, methods);
+        self.heap.track_class(&value)?;
+
+        self.environment
+            .borrow()
+            .borrow_mut()
+            .define(&class.name, Value::Class(value))
+    }
+
+    // loads the module the first time it's imported, and hands out the same one after that. It
+    // runs like a call, so errors in it are traced back to the import
+    fn import(&self, import: &Import) -> Result<Rc<Module>> {
+        let file = self.file();
+        let path = Path::new(&*file)
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join(&import.path);
+
+        let cannot_import = |err: &dyn std::fmt::Display| {
+            runtime_error(
+                &import.keyword,
+                &format!("Can't import '{}': {}", import.path, err),
+            )
+        };
+
+        let canonical = fs::canonicalize(&path).map_err(|err| cannot_import(&err))?;
+        if let Some(module) = self.modules.borrow().get(&canonical) {
+            return Ok(module.clone());
+        }
+
+        let files = self.files.borrow().clone();
+        let importing = files
+            .iter()
+            .position(|file| fs::canonicalize(&**file).ok().as_ref() == Some(&canonical));
+        if let Some(start) = importing {
+            let chain: Vec<String> = files[start..]
+                .iter()
+                .map(|file| file.to_string())
+                .chain([path.display().to_string()])
+                .collect();
+
+            return Err(runtime_error(
+                &import.keyword,
+                &format!("Circular import: {}.", chain.join(" -> ")),
+            ));
+        }
+
+        let source = fs::read(&path).map_err(|err| cannot_import(&err))?;
+        let (statements, warnings) = load(&source).map_err(|err| cannot_import(&err))?;
+        for warning in warnings {
+            self.warn(&format!("{}: {}", path.display(), warning));
+        }
+
+        let globals = Rc::new(RefCell::new(Environment::new()));
+        for (name, native) in self.natives.borrow().iter() {
+            globals.borrow_mut().bind(name, native.clone());
+        }
+        self.heap.track_environment(&globals)?;
+
+        let module_file: Rc<str> = Rc::from(path.display().to_string());
+        self.frames.borrow_mut().push(Frame {
+            function: format!("<module {}>", import.path),
+            file: Some(module_file.clone()),
+            call_site: Some(import.keyword.clone()),
+        });
+        self.files.borrow_mut().push(module_file);
+        let previous = self.environment.replace(globals.clone());
+
+        let result = self
+            .execute_all(&statements)
+            .map_err(|err| self.traced(err));
+
+        self.environment.replace(previous);
+        self.files.borrow_mut().pop();
+        self.frames.borrow_mut().pop();
+        result?;
+
+        let name = path.file_stem().map_or(import.path.clone(), |stem| {
+            stem.to_string_lossy().to_string()
+        });
+        let module = Rc::new(Module::new(name, globals, &statements));
+
+        self.modules.borrow_mut().insert(canonical, module.clone());
+
+        Ok(module)
+    }
+
+    // the error a `throw` unwinds with. An object with a message, like a caught runtime error,
+    // is reported by its message if it's never caught
+    fn throw(&self, throw: &Throw) -> Result<Error> {
+        let value = self.evaluate(&throw.value)?;
+
+        let message = match &value {
+            Value::Instance(instance) => match instance.borrow().field("message") {
+                Some(Value::String(message)) => message.clone(),
+                _ => value.to_string(),
+            },
+            value => value.to_string(),
+        };
+        self.thrown.replace(Some(value));
+
+        Ok(Error(ErrorType::Interpreter(RuntimeError {
+            thrown: true,
+            ..RuntimeError::at(&throw.keyword, &message)
+        })))
+    }
+
+    // `finally` runs however the body and catch clause finish. Unless it's left early itself,
+    // what they did carries on afterwards, including an error still being thrown. Going over
+    // one of the interpreter's limits can't be caught, and skips `finally` too
+    fn execute_try(&self, try_stmt: &Try) -> Result<Flow> {
+        let body = Environment::with_enclosing(self.environment.borrow().clone());
+
+        let result = match (self.execute_block(&try_stmt.body, body), &try_stmt.catch) {
+            (Err(Error(ErrorType::Interpreter(error))), Some(catch)) => {
+                let caught = self.caught(error)?;
+
+                let mut scope = Environment::with_enclosing(self.environment.borrow().clone());
+                scope.define(&catch.name, caught)?;
+
+                self.execute_block(&catch.body, scope)
+            }
+            (result, _) => result,
+        };
+
+        let Some(finally) = &try_stmt.finally else {
+            return result;
+        };
+        if let Err(Error(ErrorType::Limit(..))) = result {
+            return result;
+        }
+
+        let thrown = self.thrown.take();
+        let scope = Environment::with_enclosing(self.environment.borrow().clone());
+
+        match self.execute_block(finally, scope)? {
+            Flow::Next => {
+                self.thrown.replace(thrown);
+                result
+            }
+            flow => Ok(flow),
+        }
+    }
+
+    // the value a `catch` clause gets: what was thrown, or an error object with the `message`,
+    // `line` and `trace` of an error the interpreter raised
+    fn caught(&self, error: RuntimeError) -> Result<Value> {
+        if error.thrown {
+            if let Some(value) = self.thrown.take() {
+                return Ok(value);
+            }
+        }
+
+        let error = match self.traced(Error(ErrorType::Interpreter(error))) {
+            Error(ErrorType::Interpreter(error)) => error,
+            err => return Err(err),
+        };
+
+        let trace = Value::list(
+            error
+                .trace
+                .iter()
+                .map(|frame| Value::String(frame.to_string()))
+                .collect(),
+        );
+        self.heap.track_value(&trace)?;
+
+        let mut instance = LoxInstance::new(self.error_class.clone());
+        instance.set_field("message", Value::String(error.message));
+        instance.set_field(
+            "line",
+            match error.line {
+                Some(line) => Value::Number(NumberType::Integer(line as i64)),
+                None => Value::Nil,
+            },
+        );
+        instance.set_field("trace", trace);
+
+        let instance = Value::Instance(Rc::new(RefCell::new(instance)));
+        self.heap.track_value(&instance)?;
+
+        Ok(instance)
+    }
+
+    fn execute_if(&self, if_stmt: &If) -> Result<Flow> {
+        if self.evaluate(&if_stmt.condition)?.is_truthy() {
+            return self.execute(&if_stmt.then_branch);
+        }
+
+        match &if_stmt.else_branch {
+            Some(else_branch) => self.execute(else_branch),
+            None => Ok(Flow::Next),
+        }
+    }
+
+    // a `continue` still runs the increment of a desugared `for`
+    fn execute_while(&self, while_stmt: &While) -> Result<Flow> {
+        while self.evaluate(&while_stmt.condition)?.is_truthy() {
+            match self.execute(&while_stmt.body)? {
+                Flow::Break => break,
+                flow @ Flow::Return(..) => return Ok(flow),
+                Flow::Next | Flow::Continue => {}
+            }
+
+            if let Some(increment) = &while_stmt.increment {
+                self.evaluate(increment)?;
+            }
+        }
+
+        Ok(Flow::Next)
+    }
+
+    pub fn evaluate(&self, expr: &Expr) -> Result<Value> {
+        match expr {
+            Expr::Literal(literal) => Ok(Value::from(literal.value.clone())),
+            Expr::Grouping(grouping) => self.evaluate(&grouping.expression),
+            Expr::Unary(unary) => self.unary(unary),
+            Expr::Binary(binary) => self.binary(binary),
+            Expr::Logical(logical) => self.logical(logical),
+            Expr::Conditional(conditional) => {
+                if self.evaluate(&conditional.condition)?.is_truthy() {
+                    self.evaluate(&conditional.then_branch)
+                } else {
+                    self.evaluate(&conditional.else_branch)
+                }
+            }
+            Expr::Comma(comma) => {
+                self.evaluate(&comma.left)?;
+                self.evaluate(&comma.right)
+            }
+            Expr::List(list) => {
+                let items = list
+                    .elements
+                    .iter()
+                    .map(|element| self.evaluate(element))
+                    .collect::<Result<Vec<Value>>>()?;
+
+                let list = Value::list(items);
+                self.heap.track_value(&list)?;
+
+                Ok(list)
+            }
+            Expr::Map(map_expr) => {
+                let mut map = Map::new();
+                for (key, value) in &map_expr.entries {
+                    let key = self.map_key(self.evaluate(key)?, &map_expr.brace)?;
+                    map.insert(key, self.evaluate(value)?);
+                }
+
+                let map = Value::map(map);
+                self.heap.track_value(&map)?;
+
+                Ok(map)
+            }
+            Expr::Index(index) => self.index(index),
+            Expr::SetIndex(set_index) => self.set_index(set_index),
+            Expr::Error(error) => Err(Error(ErrorType::Interpreter(RuntimeError::new(&format!(
+                "Can't run code with syntax errors: {}",
+                error.message
+            ))))),
+            Expr::Variable(variable) => self.look_up(&variable.name, &variable.depth),
+            Expr::Assign(assign) => {
+                let value = self.evaluate(&assign.value)?;
+
+                match assign.depth.get() {
+                    Some(distance) => self.environment.borrow().borrow_mut().assign_at(
+                        distance,
+                        &assign.name,
+                        value.clone(),
+                    )?,
+                    None => Environment::globals(&self.environment.borrow())
+                        .borrow_mut()
+                        .assign(&assign.name, value.clone())?,
+                }
+
+                Ok(value)
+            }
+            Expr::Call(call) => self.call(call),
+            Expr::Function(function) => {
+                let value = Rc::new(LoxFunction::new(
+                    None,
+                    function.params.clone(),
+                    function.body.clone(),
+                    self.environment.borrow().clone(),
+                    self.file(),
+                ));
+                self.heap.track_function(&value)?;
+
+                Ok(Value::Callable(value))
+            }
+            Expr::This(this) => self.look_up(&this.keyword, &this.depth),
+            Expr::Get(get) => match self.evaluate(&get.object)? {
+                Value::Instance(instance) => get_property(&instance, &get.name, &self.heap),
+                Value::Module(module) => module.get(&get.name),
+                Value::Native(object) => object.get(&get.name),
+                _ => Err(runtime_error(&get.name, "Only instances have properties.")),
+            },
+            Expr::Set(set) => {
+                let instance = match self.evaluate(&set.object)? {
+                    Value::Instance(instance) => instance,
+                    Value::Native(object) => {
+                        let value = self.evaluate(&set.value)?;
+                        object.set(&set.name, value.clone())?;
+
+                        return Ok(value);
+                    }
+                    _ => return Err(runtime_error(&set.name, "Only instances have fields.")),
+                };
+
+                let value = self.evaluate(&set.value)?;
+                self.heap.store(&value)?;
+                instance.borrow_mut().set(&set.name, value.clone());
+
+                Ok(value)
+            }
+            Expr::Super(super_expr) => self.super_method(super_expr),
+        }
+    }
+
+    fn call(&self, call: &Call) -> Result<Value> {
+        let callee = self.evaluate(&call.callee)?;
+
+        let arguments = call
+            .arguments
+            .iter()
+            .map(|argument| self.evaluate(argument))
+            .collect::<Result<Vec<Value>>>()?;
+
+        self.call_value_at(callee, arguments, Some(&call.parenthesis))
+    }
+
+    /// Calls a Lox function, method or class from Rust. Outside of a running script, this is a
+    /// run of its own, with the whole allowance of steps and time.
+    pub fn call_value(&self, callee: Value, arguments: Vec<Value>) -> Result<Value> {
+        for argument in &arguments {
+            self.heap.adopt(argument)?;
+            self.heap.store(argument)?;
+        }
+
+        self.run(|| self.call_value_at(callee, arguments, None))
+    }
+
+    fn call_value_at(
+        &self,
+        callee: Value,
+        arguments: Vec<Value>,
+        call_site: Option<&Token>,
+    ) -> Result<Value> {
+        let error = |message: &str| match call_site {
+            Some(call_site) => runtime_error(call_site, message),
+            None => Error(ErrorType::Interpreter(RuntimeError::new(message))),
+        };
+
+        let callable: Rc<dyn LoxCallable> = match callee {
+            Value::Callable(callable) => callable,
+            Value::Class(class) => class,
+            _ => return Err(error("Can only call functions and classes.")),
+        };
+
+        if self.frames.borrow().len() >= self.max_call_depth.get() {
+            return Err(error("Stack overflow."));
+        }
+
+        // a call with the wrong number of arguments never starts, so it's no frame of the trace
+        check_arity(callable.arity(), arguments.len()).map_err(|message| error(&message))?;
+
+        self.frames.borrow_mut().push(Frame {
+            function: match callable.name() {
+                "" => String::from("<fn>"),
+                name => name.to_string(),
+            },
+            file: callable.file(),
+            call_site: call_site.cloned(),
+        });
+
+        let result = stacker::maybe_grow(STACK_RED_ZONE, STACK_GROWTH, || {
+            callable.call(self, arguments)
+        })
+        .map_err(|err| self.traced(err));
+
+        self.frames.borrow_mut().pop();
+
+        result
+    }
+
+    // `super.method` finds the method on the superclass of the class the call is written in,
+    // and binds it to the `this` of the method it's called from
+    fn super_method(&self, super_expr: &Super) -> Result<Value> {
+        let distance = match super_expr.depth.get() {
+            Some(distance) => distance,
+            None => return Err(runtime_error(&super_expr.keyword, "Undefined 'super'.")),
+        };
+
+        let environment = self.environment.borrow().clone();
+        let environment = environment.borrow();
+
+        let superclass = match environment.lookup_at(distance, "super") {
+            Some(Value::Class(superclass)) => superclass,
+            _ => return Err(runtime_error(&super_expr.keyword, "Undefined 'super'.")),
+        };
+        let this = environment
+            .lookup_at(distance - 1, "this")
+            .unwrap_or(Value::Nil);
+
+        match superclass.find_method(super_expr.method.lexeme()) {
+            Some(method) => Ok(Value::Callable(method.bind(this, &self.heap)?)),
+            None => Err(runtime_error(
+                &super_expr.method,
+                &format!("Undefined property '{}'.", super_expr.method.lexeme()),
+            )),
+        }
+    }
+
+    // a variable, `this` or `super`, which the resolver either found in a local scope or left as
+    // a global
+    fn look_up(&self, name: &Token, depth: &Cell<Option<usize>>) -> Result<Value> {
+        match depth.get() {
+            Some(distance) => self.environment.borrow().borrow().get_at(distance, name),
+            None => Environment::globals(&self.environment.borrow())
+                .borrow()
+                .get(name),
+        }
+    }
+
+    fn unary(&self, unary: &Unary) -> Result<Value> {
+        let right = self.evaluate(&unary.right)?;
+
+        match unary.operator.token_type() {
+            TokenType::SingleCharacters(SingleCharacter::Minus) => match right {
+                Value::Number(number) => arithmetic(&unary.operator, number::negate(number)),
+                _ => Err(runtime_error(&unary.operator, "Operand must be a number.")),
+            },
+            TokenType::SingleOrDoubles(SingleOrDouble::Bang) => Ok(Value::Bool(!right.is_truthy())),
+            _ => Err(runtime_error(&unary.operator, "Unknown unary operator.")),
+        }
+    }
+
+    fn binary(&self, binary: &Binary) -> Result<Value> {
+        let left = self.evaluate(&binary.left)?;
+        let right = self.evaluate(&binary.right)?;
+        let operator = &binary.operator;
+
+        match operator.token_type() {
+            TokenType::SingleOrDoubles(SingleOrDouble::EqualEqual) => {
+                Ok(Value::Bool(left == right))
+            }
+            TokenType::SingleOrDoubles(SingleOrDouble::BangEqual) => Ok(Value::Bool(left != right)),
+            TokenType::SingleCharacters(SingleCharacter::Plus) => match (left, right) {
+                (Value::Number(left), Value::Number(right)) => {
+                    arithmetic(operator, number::add(left, right))
+                }
+                (Value::String(left), Value::String(right)) => {
+                    self.heap.allocate(left.len() + right.len())?;
+                    Ok(Value::String(left + &right))
+                }
+                _ => Err(runtime_error(
+                    operator,
+                    "Operands must be two numbers or two strings.",
+                )),
+            },
+            token_type => {
+                let (left, right) = match (left, right) {
+                    (Value::Number(left), Value::Number(right)) => (left, right),
+                    _ => return Err(runtime_error(operator, "Operands must be numbers.")),
+                };
+
+                let ordering = number::compare(left, right);
+
+                match token_type {
+                    TokenType::SingleCharacters(SingleCharacter::Minus) => {
+                        arithmetic(operator, number::subtract(left, right))
+                    }
+                    TokenType::SingleCharacters(SingleCharacter::Star) => {
+                        arithmetic(operator, number::multiply(left, right))
+                    }
+                    TokenType::SingleCharacters(SingleCharacter::Slash) => {
+                        arithmetic(operator, number::divide(left, right))
+                    }
+                    TokenType::SingleOrDoubles(SingleOrDouble::Greater) => {
+                        Ok(Value::Bool(ordering == Some(Ordering::Greater)))
+                    }
+                    TokenType::SingleOrDoubles(SingleOrDouble::GreaterEqual) => Ok(Value::Bool(
+                        matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+                    )),
+                    TokenType::SingleOrDoubles(SingleOrDouble::Less) => {
+                        Ok(Value::Bool(ordering == Some(Ordering::Less)))
+                    }
+                    TokenType::SingleOrDoubles(SingleOrDouble::LessEqual) => Ok(Value::Bool(
+                        matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                    )),
+                    _ => Err(runtime_error(operator, "Unknown binary operator.")),
+                }
+            }
+        }
+    }
+
+    // `or` yields its left operand when that's truthy, `and` when it's falsey, without
+    // evaluating the right one
+    fn logical(&self, logical: &Logical) -> Result<Value> {
+        let left = self.evaluate(&logical.left)?;
+
+        let short_circuits = match logical.operator.token_type() {
+            TokenType::Keywords(Keyword::Or) => left.is_truthy(),
+            _ => !left.is_truthy(),
+        };
+
+        if short_circuits {
+            return Ok(left);
+        }
+
+        self.evaluate(&logical.right)
+    }
+
+    fn index(&self, index: &Index) -> Result<Value> {
+        let object = self.evaluate(&index.object)?;
+        let key = self.evaluate(&index.index)?;
+
+        match object {
+            Value::List(list) => {
+                let position = self.list_index(key, &index.bracket)?;
+                let item = list.borrow().get(position, &index.bracket)?.clone();
+
+                Ok(item)
+            }
+            Value::Map(map) => {
+                let key = self.map_key(key, &index.bracket)?;
+
+                match map.borrow().get(&key) {
+                    Some(value) => Ok(value.clone()),
+                    None => Err(runtime_error(
+                        &index.bracket,
+                        &format!("Map has no key {}.", key),
+                    )),
+                }
+            }
+            object => Err(runtime_error(
+                &index.bracket,
+                &format!(
+                    "Only lists and maps can be indexed, got {}.",
+                    object.type_name()
+                ),
+            )),
+        }
+    }
+
+    fn set_index(&self, set_index: &SetIndex) -> Result<Value> {
+        let object = self.evaluate(&set_index.object)?;
+        let key = self.evaluate(&set_index.index)?;
+        let value = self.evaluate(&set_index.value)?;
+        self.heap.store(&value)?;
+
+        match object {
+            Value::List(list) => {
+                let position = self.list_index(key, &set_index.bracket)?;
+                list.borrow_mut()
+                    .set(position, value.clone(), &set_index.bracket)?;
+            }
+            Value::Map(map) => {
+                let key = self.map_key(key, &set_index.bracket)?;
+                map.borrow_mut().insert(key, value.clone());
+            }
+            object => {
+                return Err(runtime_error(
+                    &set_index.bracket,
+                    &format!(
+                        "Only lists and maps can be indexed, got {}.",
+                        object.type_name()
+                    ),
+                ))
+            }
+        }
+
+        Ok(value)
+    }
+
+    fn list_index(&self, index: Value, bracket: &Token) -> Result<NumberType> {
+        match index {
+            Value::Number(number) => Ok(number),
+            index => Err(runtime_error(
+                bracket,
+                &format!("List index must be a number, got {}.", index.type_name()),
+            )),
+        }
+    }
+
+    fn map_key(&self, key: Value, token: &Token) -> Result<MapKey> {
+        match key {
+            Value::String(string) => Ok(MapKey::String(string)),
+            Value::Number(number) => MapKey::number(number, token),
+            Value::Bool(b) => Ok(MapKey::Bool(b)),
+            Value::Nil => Ok(MapKey::Nil),
+            key => Err(runtime_error(
+                token,
+                &format!(
+                    "Map keys must be strings, numbers, booleans or nil, got {}.",
+                    key.type_name()
+                ),
+            )),
+        }
+    }
+}
+
+// reads a module ready to run, along with the resolver's warnings for it
+fn load(source: &[u8]) -> Result<(Vec<Stmt>, Vec<String>)> {
+    let tokens = Scanner::new(source)
+        .scan_tokens()
+        .map_err(Error::completed)?
+        .to_vec();
+    let statements = Parser::new(tokens).parse().map_err(Error::completed)?;
+    let warnings = Resolver::new().resolve(&statements)?;
+
+    Ok((statements, warnings))
+}
+
+fn check_arity(arity: Arity, count: usize) -> std::result::Result<(), String> {
+    if arity.accepts(count) {
+        return Ok(());
+    }
+
+    let expected = match arity {
+        Arity::Fixed(arity) => arity.to_string(),
+        Arity::Variadic(min) => format!("at least {}", min),
+    };
+
+    Err(format!(
+        "Expected {} arguments but got {}.",
+        expected, count
+    ))
+}
+
+fn arithmetic(operator: &Token, result: number::Result) -> Result<Value> {
+    result
+        .map(Value::Number)
+        .map_err(|err| runtime_error(operator, err.message()))
+}
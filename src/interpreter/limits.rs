@@ -0,0 +1,70 @@
+use crate::errors::{Error, ErrorType, LimitExceeded};
+use crate::Result;
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+/// Caps on how much work a script can do, for running code that isn't trusted. Each run through
+/// `Interpreter::interpret` gets the full allowance of steps and time again.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Limits {
+    /// How many statements can be executed.
+    pub max_steps: Option<u64>,
+    /// How long the script can run for.
+    pub timeout: Option<Duration>,
+    /// How many bytes the objects a script keeps alive can take up. Unlike the other limits,
+    /// this one carries over between runs, since the objects do too.
+    pub max_memory: Option<usize>,
+}
+
+// the clock is only read every this many steps, since reading it costs more than a step
+const CLOCK_INTERVAL: u64 = 1024;
+
+/// What's left of the limits for the run in progress.
+#[derive(Debug, Default)]
+pub struct Budget {
+    limits: Cell<Limits>,
+    steps: Cell<u64>,
+    deadline: Cell<Option<Instant>>,
+}
+
+impl Budget {
+    pub fn limits(&self) -> Limits {
+        self.limits.get()
+    }
+
+    pub fn set_limits(&self, limits: Limits) {
+        self.limits.set(limits);
+    }
+
+    /// Starts a run with the whole allowance.
+    pub fn start(&self) {
+        self.steps.set(0);
+        self.deadline.set(
+            self.limits()
+                .timeout
+                .map(|timeout| Instant::now() + timeout),
+        );
+    }
+
+    /// Takes one step off the budget, failing once it has run out.
+    pub fn step(&self) -> Result<()> {
+        let steps = self.steps.get() + 1;
+        self.steps.set(steps);
+
+        let limits = self.limits();
+
+        if let Some(max_steps) = limits.max_steps {
+            if steps > max_steps {
+                return Err(Error(ErrorType::Limit(LimitExceeded::Steps(max_steps))));
+            }
+        }
+
+        if let (Some(deadline), Some(timeout)) = (self.deadline.get(), limits.timeout) {
+            if steps.is_multiple_of(CLOCK_INTERVAL) && Instant::now() >= deadline {
+                return Err(Error(ErrorType::Limit(LimitExceeded::Timeout(timeout))));
+            }
+        }
+
+        Ok(())
+    }
+}
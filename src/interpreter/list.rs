@@ -0,0 +1,100 @@
+use crate::interpreter::heap::{HeapSize, Trace};
+use crate::interpreter::number::as_integer;
+use crate::interpreter::runtime_error;
+use crate::scanner::token::Token;
+use crate::scanner::token_type::NumberType;
+use crate::Result;
+use std::mem::size_of;
+
+/// The storage behind a Lox list. Indices come straight from scripts, so every access is bounds
+/// checked and failures are reported at the `]` of the indexing expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct List<T> {
+    items: Vec<T>,
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        Self { items: vec![] }
+    }
+}
+
+impl<T> List<T> {
+    pub fn new(items: Vec<T>) -> Self {
+        Self { items }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items.iter()
+    }
+
+    pub fn get(&self, index: NumberType, bracket: &Token) -> Result<&T> {
+        let position = self.position(index, bracket)?;
+
+        Ok(&self.items[position])
+    }
+
+    pub fn set(&mut self, index: NumberType, value: T, bracket: &Token) -> Result<()> {
+        let position = self.position(index, bracket)?;
+        self.items[position] = value;
+
+        Ok(())
+    }
+
+    // floats with nothing after the point index too, since `/` always gives a float
+    fn position(&self, index: NumberType, bracket: &Token) -> Result<usize> {
+        let index = match as_integer(index) {
+            Some(index) => index,
+            None => {
+                return Err(runtime_error(
+                    bracket,
+                    &format!("List index must be an integer, got {}.", index),
+                ))
+            }
+        };
+
+        if index < 0 {
+            return Err(runtime_error(
+                bracket,
+                &format!("List index can't be negative, got {}.", index),
+            ));
+        }
+
+        match usize::try_from(index) {
+            Ok(position) if position < self.items.len() => Ok(position),
+            _ => Err(runtime_error(
+                bracket,
+                &format!(
+                    "List index {} is out of bounds for length {}.",
+                    index,
+                    self.items.len()
+                ),
+            )),
+        }
+    }
+}
+
+impl<T: HeapSize> HeapSize for List<T> {
+    fn heap_size(&self) -> usize {
+        self.items
+            .iter()
+            .map(|item| size_of::<T>() + item.heap_size())
+            .sum()
+    }
+}
+
+impl<T: Trace> Trace for List<T> {
+    fn trace(&self, references: &mut Vec<usize>) {
+        for item in &self.items {
+            item.trace(references);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::interpreter::heap::{HeapSize, Trace};
+use crate::interpreter::number::as_integer;
+use crate::interpreter::runtime_error;
+use crate::scanner::token::Token;
+use crate::scanner::token_type::NumberType;
+use crate::Result;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::mem::size_of;
+
+/// A hashable map key. Numbers compare by value, so `1` and `1.0` name the same entry.
+#[derive(Clone, Debug)]
+pub enum MapKey {
+    String(String),
+    Number(NumberType),
+    Bool(bool),
+    Nil,
+}
+
+impl MapKey {
+    /// NaN is never equal to itself, so it can't be looked up again and is rejected as a key.
+    pub fn number(number: NumberType, bracket: &Token) -> Result<Self> {
+        match number {
+            NumberType::Float(float) if float.is_nan() => {
+                Err(runtime_error(bracket, "Map key can't be NaN."))
+            }
+            _ => Ok(MapKey::Number(number)),
+        }
+    }
+}
+
+impl PartialEq for MapKey {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (MapKey::String(left), MapKey::String(right)) => left == right,
+            (MapKey::Number(left), MapKey::Number(right)) => {
+                match (as_integer(*left), as_integer(*right)) {
+                    (Some(left), Some(right)) => left == right,
+                    (None, None) => left == right,
+                    _ => false,
+                }
+            }
+            (MapKey::Bool(left), MapKey::Bool(right)) => left == right,
+            (MapKey::Nil, MapKey::Nil) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for MapKey {}
+
+impl Display for MapKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MapKey::String(s) => write!(f, "{:?}", s),
+            MapKey::Number(number) => write!(f, "{}", number),
+            MapKey::Bool(b) => write!(f, "{}", b),
+            MapKey::Nil => write!(f, "nil"),
+        }
+    }
+}
+
+impl Hash for MapKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+
+        match self {
+            MapKey::String(s) => s.hash(state),
+            MapKey::Number(number) => match (as_integer(*number), number) {
+                (Some(int), _) => int.hash(state),
+                (None, NumberType::Float(float)) => float.to_bits().hash(state),
+                (None, NumberType::Integer(int)) => int.hash(state),
+            },
+            MapKey::Bool(b) => b.hash(state),
+            MapKey::Nil => {}
+        }
+    }
+}
+
+/// The storage behind a Lox map. Entries keep their insertion order so printing is stable.
+#[derive(Clone, Debug)]
+pub struct Map<V> {
+    entries: Vec<(MapKey, V)>,
+    positions: HashMap<MapKey, usize>,
+}
+
+impl<V> Default for Map<V> {
+    fn default() -> Self {
+        Self {
+            entries: vec![],
+            positions: HashMap::new(),
+        }
+    }
+}
+
+impl<V> Map<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&MapKey, &V)> {
+        self.entries.iter().map(|(key, value)| (key, value))
+    }
+
+    pub fn get(&self, key: &MapKey) -> Option<&V> {
+        self.positions
+            .get(key)
+            .map(|position| &self.entries[*position].1)
+    }
+
+    /// Overwrites the value of an existing key in place, keeping its original position.
+    pub fn insert(&mut self, key: MapKey, value: V) {
+        match self.positions.get(&key) {
+            Some(position) => self.entries[*position].1 = value,
+            None => {
+                self.positions.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+            }
+        }
+    }
+}
+
+// each key is stored twice, once with its entry and once in the index
+impl<V: HeapSize> HeapSize for Map<V> {
+    fn heap_size(&self) -> usize {
+        self.entries
+            .iter()
+            .map(|(key, value)| {
+                let key_size = match key {
+                    MapKey::String(string) => string.len(),
+                    _ => 0,
+                };
+
+                size_of::<(MapKey, V)>()
+                    + size_of::<(MapKey, usize)>()
+                    + 2 * key_size
+                    + value.heap_size()
+            })
+            .sum()
+    }
+}
+
+impl<V: Trace> Trace for Map<V> {
+    fn trace(&self, references: &mut Vec<usize>) {
+        for (_, value) in &self.entries {
+            value.trace(references);
+        }
+    }
+}
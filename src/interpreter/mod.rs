@@ -0,0 +1,24 @@
+use crate::errors::{Error, ErrorType, RuntimeError};
+use crate::scanner::token::Token;
+
+pub mod callable;
+pub mod class;
+pub mod convert;
+pub mod environment;
+pub mod function;
+pub mod heap;
+#[allow(clippy::module_inception)]
+pub mod interpreter;
+pub mod limits;
+pub mod list;
+pub mod map;
+pub mod module;
+pub mod native;
+pub mod native_class;
+pub mod number;
+pub mod value;
+
+/// A runtime error located at the token that caused it.
+pub fn runtime_error(token: &Token, message: &str) -> Error {
+    Error(ErrorType::Interpreter(RuntimeError::at(token, message)))
+}
@@ -0,0 +1,70 @@
+use crate::interpreter::environment::Environment;
+use crate::interpreter::runtime_error;
+use crate::interpreter::value::Value;
+use crate::scanner::token::Token;
+use crate::stmt::Stmt;
+use crate::Result;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+
+/// A file loaded with `import`. It runs in globals of its own, which the functions it declares
+/// keep using wherever they're called from, and its top-level declarations are read off it by
+/// name.
+pub struct Module {
+    name: String,
+    globals: Rc<RefCell<Environment>>,
+    exports: HashSet<String>,
+}
+
+impl Module {
+    /// `statements` are the top level of the module, which has been run in `globals`.
+    pub fn new(name: String, globals: Rc<RefCell<Environment>>, statements: &[Stmt]) -> Self {
+        let exports = statements
+            .iter()
+            .filter_map(|statement| match statement {
+                Stmt::Var(var) => Some(&var.name),
+                Stmt::Function(function) => Some(&function.name),
+                Stmt::Class(class) => Some(&class.name),
+                Stmt::Import(import) => Some(&import.name),
+                _ => None,
+            })
+            .map(|name| name.lexeme().to_string())
+            .collect();
+
+        Self {
+            name,
+            globals,
+            exports,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The current value of a top-level declaration, which may have changed since the module
+    /// was loaded.
+    pub fn get(&self, name: &Token) -> Result<Value> {
+        if !self.exports.contains(name.lexeme()) {
+            return Err(runtime_error(
+                name,
+                &format!(
+                    "Module '{}' has no declaration '{}'.",
+                    self.name,
+                    name.lexeme()
+                ),
+            ));
+        }
+
+        self.globals.borrow().get(name)
+    }
+}
+
+// the globals are left out, they can refer back to the module itself
+impl Debug for Module {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Module").field("name", &self.name).finish()
+    }
+}
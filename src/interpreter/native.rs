@@ -0,0 +1,174 @@
+use crate::interpreter::callable::{Arity, LoxCallable};
+use crate::interpreter::interpreter::Interpreter;
+use crate::interpreter::map::{Map, MapKey};
+use crate::interpreter::number::negate;
+use crate::interpreter::value::Value;
+use crate::scanner::scanner::parse_number;
+use crate::scanner::token_type::NumberType;
+use crate::Result;
+use std::fmt::{Debug, Formatter};
+use std::io;
+use std::io::Write;
+use std::rc::Rc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The Rust side of a native function. An `Err` is a message, reported as a runtime error at the
+/// call.
+pub type NativeFn = dyn Fn(&Interpreter, &[Value]) -> std::result::Result<Value, String>;
+
+/// A function implemented in Rust and callable from Lox like any other.
+pub struct NativeFunction {
+    name: String,
+    arity: Arity,
+    function: Rc<NativeFn>,
+}
+
+impl NativeFunction {
+    pub fn new<F>(name: &str, arity: Arity, function: F) -> Self
+    where
+        F: Fn(&Interpreter, &[Value]) -> std::result::Result<Value, String> + 'static,
+    {
+        Self {
+            name: name.to_string(),
+            arity,
+            function: Rc::new(function),
+        }
+    }
+}
+
+impl Debug for NativeFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NativeFunction")
+            .field("name", &self.name)
+            .field("arity", &self.arity)
+            .finish()
+    }
+}
+
+impl LoxCallable for NativeFunction {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn arity(&self) -> Arity {
+        self.arity
+    }
+
+    fn call(&self, interpreter: &Interpreter, arguments: Vec<Value>) -> Result<Value> {
+        let value = (self.function)(interpreter, &arguments)
+            .map_err(|message| interpreter.error_at_call(&message))?;
+        interpreter.heap().store(&value)?;
+
+        Ok(value)
+    }
+
+    fn describe(&self) -> String {
+        format!("<native fn {}>", self.name)
+    }
+}
+
+/// The built-ins every interpreter starts with.
+pub fn core() -> Vec<NativeFunction> {
+    vec![
+        NativeFunction::new("clock", Arity::Fixed(0), clock),
+        NativeFunction::new("str", Arity::Fixed(1), |_, arguments| {
+            Ok(Value::String(arguments[0].to_string()))
+        }),
+        NativeFunction::new("num", Arity::Fixed(1), num),
+        NativeFunction::new("type", Arity::Fixed(1), |_, arguments| {
+            Ok(Value::String(arguments[0].type_name().to_string()))
+        }),
+        NativeFunction::new("len", Arity::Fixed(1), len),
+        NativeFunction::new("input", Arity::Variadic(0), input),
+        NativeFunction::new("gc", Arity::Fixed(0), |interpreter, _| {
+            let freed = interpreter.heap().collect();
+
+            Ok(Value::Number(NumberType::Integer(freed as i64)))
+        }),
+        NativeFunction::new("gcStats", Arity::Fixed(0), gc_stats),
+    ]
+}
+
+// seconds since the epoch, for timing scripts
+fn clock(_: &Interpreter, _: &[Value]) -> std::result::Result<Value, String> {
+    let elapsed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| err.to_string())?;
+
+    Ok(Value::Number(NumberType::Float(elapsed.as_secs_f64())))
+}
+
+// a number parsed from a string, following the same integer and float rules as literals. A
+// leading `-` negates it, as it would a literal
+fn num(_: &Interpreter, arguments: &[Value]) -> std::result::Result<Value, String> {
+    match &arguments[0] {
+        Value::Number(number) => Ok(Value::Number(*number)),
+        Value::String(string) => {
+            let trimmed = string.trim();
+            let number = match trimmed.strip_prefix('-') {
+                Some(positive) => parse_number(positive).and_then(|number| negate(number).ok()),
+                None => parse_number(trimmed),
+            };
+
+            number
+                .map(Value::Number)
+                .ok_or_else(|| format!("Can't convert {:?} to a number.", string))
+        }
+        value => Err(format!(
+            "Can't convert a {} to a number.",
+            value.type_name()
+        )),
+    }
+}
+
+fn len(_: &Interpreter, arguments: &[Value]) -> std::result::Result<Value, String> {
+    let length = match &arguments[0] {
+        Value::String(string) => string.chars().count(),
+        Value::List(list) => list.borrow().len(),
+        Value::Map(map) => map.borrow().len(),
+        value => return Err(format!("A {} has no length.", value.type_name())),
+    };
+
+    Ok(Value::Number(NumberType::Integer(length as i64)))
+}
+
+// a line from stdin without its line ending, or nil at the end of input. Any arguments are
+// printed first as a prompt
+fn input(_: &Interpreter, arguments: &[Value]) -> std::result::Result<Value, String> {
+    let prompt: Vec<String> = arguments.iter().map(Value::to_string).collect();
+    print!("{}", prompt.join(" "));
+    io::stdout().flush().map_err(|err| err.to_string())?;
+
+    let mut line = String::new();
+    let read = io::stdin()
+        .read_line(&mut line)
+        .map_err(|err| err.to_string())?;
+
+    if read == 0 {
+        return Ok(Value::Nil);
+    }
+
+    let line = line.strip_suffix('\n').unwrap_or(&line);
+    let line = line.strip_suffix('\r').unwrap_or(line);
+
+    Ok(Value::String(line.to_string()))
+}
+
+// the heap's numbers as a map, for scripts watching their own memory
+fn gc_stats(interpreter: &Interpreter, _: &[Value]) -> std::result::Result<Value, String> {
+    let stats = interpreter.heap().stats();
+
+    let mut map = Map::new();
+    for (key, number) in [
+        ("live_objects", stats.live_objects),
+        ("bytes", stats.bytes),
+        ("collections", stats.collections),
+    ] {
+        map.insert(
+            MapKey::String(key.to_string()),
+            Value::Number(NumberType::Integer(number as i64)),
+        );
+    }
+
+    Ok(Value::map(map))
+}
@@ -0,0 +1,342 @@
+use crate::interpreter::callable::{Arity, LoxCallable};
+use crate::interpreter::convert::{FromValue, IntoValue};
+use crate::interpreter::interpreter::Interpreter;
+use crate::interpreter::runtime_error;
+use crate::interpreter::value::Value;
+use crate::scanner::token::Token;
+use crate::Result;
+use std::any::{Any, TypeId};
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+// the Rust side of the class's members, with the type of the object already checked. An `Err`
+// is a message, as it is for natives
+type MethodFn = dyn Fn(&Interpreter, &NativeObject, &[Value]) -> std::result::Result<Value, String>;
+type GetterFn = dyn Fn(&NativeObject) -> std::result::Result<Value, String>;
+type SetterFn = dyn Fn(&NativeObject, Value) -> std::result::Result<(), String>;
+type ConstructorFn = dyn Fn(&Interpreter, &[Value]) -> std::result::Result<Box<dyn Any>, String>;
+
+struct Method {
+    arity: Arity,
+    function: Rc<MethodFn>,
+}
+
+/// A Rust type made available to Lox as a class. Its objects are the Rust values themselves,
+/// with methods and properties that call into Rust. Build one with `NativeClass::builder`.
+pub struct NativeClass {
+    members: Rc<Members>,
+}
+
+// what the class shares with its objects
+struct Members {
+    name: String,
+    type_id: TypeId,
+    constructor: Option<(Arity, Box<ConstructorFn>)>,
+    methods: HashMap<String, Rc<Method>>,
+    getters: HashMap<String, Box<GetterFn>>,
+    setters: HashMap<String, Box<SetterFn>>,
+}
+
+impl NativeClass {
+    /// Starts a class named `name` for objects of type `T`.
+    pub fn builder<T: Any>(name: &str) -> ClassBuilder<T> {
+        ClassBuilder {
+            class: Members {
+                name: name.to_string(),
+                type_id: TypeId::of::<T>(),
+                constructor: None,
+                methods: HashMap::new(),
+                getters: HashMap::new(),
+                setters: HashMap::new(),
+            },
+            object: PhantomData,
+        }
+    }
+
+    /// The type of the Rust values the class is for.
+    pub fn object_type(&self) -> TypeId {
+        self.members.type_id
+    }
+
+    /// Wraps `data` as an object of the class, and registers it with the interpreter's heap. It
+    /// has to be of the class's type.
+    pub(crate) fn instantiate(
+        &self,
+        interpreter: &Interpreter,
+        data: Box<dyn Any>,
+    ) -> Result<Value> {
+        debug_assert_eq!((*data).type_id(), self.members.type_id);
+
+        let object = Rc::new(NativeObject {
+            class: self.members.clone(),
+            data: RefCell::new(data),
+        });
+        interpreter.heap().track_native(&object)?;
+
+        Ok(Value::Native(object))
+    }
+}
+
+impl Debug for NativeClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NativeClass")
+            .field("name", &self.members.name)
+            .finish()
+    }
+}
+
+// called from Lox, the class builds an object with its constructor
+impl LoxCallable for NativeClass {
+    fn name(&self) -> &str {
+        &self.members.name
+    }
+
+    fn arity(&self) -> Arity {
+        match &self.members.constructor {
+            Some((arity, _)) => *arity,
+            None => Arity::Variadic(0),
+        }
+    }
+
+    fn call(&self, interpreter: &Interpreter, arguments: Vec<Value>) -> Result<Value> {
+        let Some((_, constructor)) = &self.members.constructor else {
+            return Err(interpreter.error_at_call(&format!(
+                "{} objects can't be created from Lox.",
+                self.members.name
+            )));
+        };
+
+        let data = constructor(interpreter, &arguments)
+            .map_err(|message| interpreter.error_at_call(&message))?;
+
+        self.instantiate(interpreter, data)
+    }
+
+    fn describe(&self) -> String {
+        self.members.name.clone()
+    }
+}
+
+/// Adds the members of a `NativeClass` for objects of type `T`.
+pub struct ClassBuilder<T> {
+    class: Members,
+    object: PhantomData<T>,
+}
+
+impl<T: Any> ClassBuilder<T> {
+    /// Lets Lox create objects by calling the class, which otherwise only Rust can do.
+    pub fn constructor<F>(mut self, arity: Arity, constructor: F) -> Self
+    where
+        F: Fn(&Interpreter, &[Value]) -> std::result::Result<T, String> + 'static,
+    {
+        self.class.constructor = Some((
+            arity,
+            Box::new(move |interpreter, arguments| {
+                constructor(interpreter, arguments).map(|data| Box::new(data) as Box<dyn Any>)
+            }),
+        ));
+
+        self
+    }
+
+    /// A method that reads the object.
+    pub fn method<F>(self, name: &str, arity: Arity, method: F) -> Self
+    where
+        F: Fn(&Interpreter, &T, &[Value]) -> std::result::Result<Value, String> + 'static,
+    {
+        self.add_method(name, arity, move |interpreter, object, arguments| {
+            method(interpreter, &*object.borrow_as()?, arguments)
+        })
+    }
+
+    /// A method that changes the object. While it runs, nothing else can use the object.
+    pub fn method_mut<F>(self, name: &str, arity: Arity, method: F) -> Self
+    where
+        F: Fn(&Interpreter, &mut T, &[Value]) -> std::result::Result<Value, String> + 'static,
+    {
+        self.add_method(name, arity, move |interpreter, object, arguments| {
+            method(interpreter, &mut *object.borrow_mut_as()?, arguments)
+        })
+    }
+
+    /// A property read with `object.name`.
+    pub fn getter<F, V>(mut self, name: &str, getter: F) -> Self
+    where
+        F: Fn(&T) -> V + 'static,
+        V: IntoValue,
+    {
+        self.class.getters.insert(
+            name.to_string(),
+            Box::new(move |object| Ok(getter(&*object.borrow_as()?).into_value())),
+        );
+
+        self
+    }
+
+    /// A property assigned with `object.name = value`. Values that don't convert to `V` are a
+    /// runtime error.
+    pub fn setter<F, V>(mut self, name: &str, setter: F) -> Self
+    where
+        F: Fn(&mut T, V) + 'static,
+        V: FromValue,
+    {
+        self.class.setters.insert(
+            name.to_string(),
+            Box::new(move |object, value| {
+                let value = V::from_value(value)?;
+                setter(&mut *object.borrow_mut_as()?, value);
+
+                Ok(())
+            }),
+        );
+
+        self
+    }
+
+    pub fn build(self) -> NativeClass {
+        NativeClass {
+            members: Rc::new(self.class),
+        }
+    }
+
+    fn add_method<F>(mut self, name: &str, arity: Arity, function: F) -> Self
+    where
+        F: Fn(&Interpreter, &NativeObject, &[Value]) -> std::result::Result<Value, String>
+            + 'static,
+    {
+        self.class.methods.insert(
+            name.to_string(),
+            Rc::new(Method {
+                arity,
+                function: Rc::new(function),
+            }),
+        );
+
+        self
+    }
+}
+
+/// An object of a `NativeClass`, holding the Rust value it stands for.
+pub struct NativeObject {
+    class: Rc<Members>,
+    data: RefCell<Box<dyn Any>>,
+}
+
+impl NativeObject {
+    pub fn class_name(&self) -> &str {
+        &self.class.name
+    }
+
+    pub fn is<T: Any>(&self) -> bool {
+        self.class.type_id == TypeId::of::<T>()
+    }
+
+    /// The Rust value, if it's a `T` and isn't being changed.
+    pub fn downcast<T: Any>(&self) -> Option<Ref<'_, T>> {
+        let data = self.data.try_borrow().ok()?;
+
+        Ref::filter_map(data, |data| (**data).downcast_ref::<T>()).ok()
+    }
+
+    /// The Rust value to change, if it's a `T` and isn't in use.
+    pub fn downcast_mut<T: Any>(&self) -> Option<RefMut<'_, T>> {
+        let data = self.data.try_borrow_mut().ok()?;
+
+        RefMut::filter_map(data, |data| (**data).downcast_mut::<T>()).ok()
+    }
+
+    /// Reads `object.name`, a property or a method bound to the object.
+    pub fn get(self: &Rc<Self>, name: &Token) -> Result<Value> {
+        if let Some(getter) = self.class.getters.get(name.lexeme()) {
+            return getter(self).map_err(|message| runtime_error(name, &message));
+        }
+
+        match self.class.methods.get(name.lexeme()) {
+            Some(method) => Ok(Value::Callable(Rc::new(BoundMethod {
+                name: name.lexeme().to_string(),
+                object: self.clone(),
+                method: method.clone(),
+            }))),
+            None => Err(runtime_error(
+                name,
+                &format!("Undefined property '{}'.", name.lexeme()),
+            )),
+        }
+    }
+
+    /// Assigns `object.name = value` through the property's setter.
+    pub fn set(&self, name: &Token, value: Value) -> Result<()> {
+        let Some(setter) = self.class.setters.get(name.lexeme()) else {
+            return Err(runtime_error(
+                name,
+                &format!(
+                    "Can't set property '{}' on {}.",
+                    name.lexeme(),
+                    self.class.name
+                ),
+            ));
+        };
+
+        setter(self, value).map_err(|message| runtime_error(name, &message))
+    }
+
+    // the value for a member of the class, which only fails when it's in use
+    fn borrow_as<T: Any>(&self) -> std::result::Result<Ref<'_, T>, String> {
+        self.downcast()
+            .ok_or_else(|| format!("The {} is being changed.", self.class.name))
+    }
+
+    fn borrow_mut_as<T: Any>(&self) -> std::result::Result<RefMut<'_, T>, String> {
+        self.downcast_mut()
+            .ok_or_else(|| format!("The {} is already in use.", self.class.name))
+    }
+}
+
+impl Debug for NativeObject {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NativeObject")
+            .field("class", &self.class.name)
+            .finish()
+    }
+}
+
+// a method read off an object, ready to be called
+struct BoundMethod {
+    name: String,
+    object: Rc<NativeObject>,
+    method: Rc<Method>,
+}
+
+impl Debug for BoundMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BoundMethod")
+            .field("name", &self.name)
+            .field("object", &self.object)
+            .finish()
+    }
+}
+
+impl LoxCallable for BoundMethod {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn arity(&self) -> Arity {
+        self.method.arity
+    }
+
+    fn call(&self, interpreter: &Interpreter, arguments: Vec<Value>) -> Result<Value> {
+        let value = (self.method.function)(interpreter, &self.object, &arguments)
+            .map_err(|message| interpreter.error_at_call(&message))?;
+        interpreter.heap().store(&value)?;
+
+        Ok(value)
+    }
+
+    fn describe(&self) -> String {
+        format!("<native fn {}>", self.name)
+    }
+}
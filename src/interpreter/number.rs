@@ -0,0 +1,135 @@
+//! Arithmetic and comparison across Lox's two kinds of number.
+//!
+//! - `+`, `-` and `*` on two integers give an integer. Overflowing `i64` is a runtime error
+//!   rather than wrapping or silently turning into a float.
+//! - `/` always gives a float, so `7 / 2` is `3.5` and `6 / 3` is `2.0`. Dividing by zero, of
+//!   either kind, is a runtime error.
+//! - Any operation with a float operand promotes the integer and gives a float.
+//! - Comparison and equality are by value across kinds and exact, so `1 == 1.0` holds but
+//!   `9007199254740993 == 9007199254740992.0` doesn't.
+//! - Integers print as they're written. Floats always print a fractional part, so `2.0` stays
+//!   `2.0` and never reads as an integer.
+
+use crate::scanner::token_type::NumberType;
+use std::cmp::Ordering;
+
+/// Why an arithmetic operation has no result.
+#[derive(Debug, PartialEq)]
+pub enum ArithmeticError {
+    Overflow,
+    DivisionByZero,
+}
+
+impl ArithmeticError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            ArithmeticError::Overflow => "Integer overflow.",
+            ArithmeticError::DivisionByZero => "Division by zero.",
+        }
+    }
+}
+
+pub type Result = std::result::Result<NumberType, ArithmeticError>;
+
+pub fn add(left: NumberType, right: NumberType) -> Result {
+    integer_or_float(left, right, i64::checked_add, |left, right| left + right)
+}
+
+pub fn subtract(left: NumberType, right: NumberType) -> Result {
+    integer_or_float(left, right, i64::checked_sub, |left, right| left - right)
+}
+
+pub fn multiply(left: NumberType, right: NumberType) -> Result {
+    integer_or_float(left, right, i64::checked_mul, |left, right| left * right)
+}
+
+pub fn divide(left: NumberType, right: NumberType) -> Result {
+    if as_float(right) == 0.0 {
+        return Err(ArithmeticError::DivisionByZero);
+    }
+
+    Ok(NumberType::Float(as_float(left) / as_float(right)))
+}
+
+pub fn negate(number: NumberType) -> Result {
+    match number {
+        NumberType::Integer(int) => int
+            .checked_neg()
+            .map(NumberType::Integer)
+            .ok_or(ArithmeticError::Overflow),
+        NumberType::Float(float) => Ok(NumberType::Float(-float)),
+    }
+}
+
+/// `None` when either side is NaN, which is unordered against everything.
+pub fn compare(left: NumberType, right: NumberType) -> Option<Ordering> {
+    match (left, right) {
+        (NumberType::Integer(left), NumberType::Integer(right)) => Some(left.cmp(&right)),
+        (NumberType::Float(left), NumberType::Float(right)) => left.partial_cmp(&right),
+        (NumberType::Integer(int), NumberType::Float(float)) => compare_mixed(int, float),
+        (NumberType::Float(float), NumberType::Integer(int)) => {
+            compare_mixed(int, float).map(Ordering::reverse)
+        }
+    }
+}
+
+pub fn equals(left: NumberType, right: NumberType) -> bool {
+    compare(left, right) == Some(Ordering::Equal)
+}
+
+pub fn as_float(number: NumberType) -> f64 {
+    match number {
+        NumberType::Integer(int) => int as f64,
+        NumberType::Float(float) => float,
+    }
+}
+
+/// The integer a number is equal to, if there is one.
+pub fn as_integer(number: NumberType) -> Option<i64> {
+    match number {
+        NumberType::Integer(int) => Some(int),
+        NumberType::Float(float)
+            if float.fract() == 0.0 && float >= i64::MIN as f64 && float < i64::MAX as f64 =>
+        {
+            Some(float as i64)
+        }
+        NumberType::Float(..) => None,
+    }
+}
+
+fn integer_or_float(
+    left: NumberType,
+    right: NumberType,
+    integer: fn(i64, i64) -> Option<i64>,
+    float: fn(f64, f64) -> f64,
+) -> Result {
+    match (left, right) {
+        (NumberType::Integer(left), NumberType::Integer(right)) => integer(left, right)
+            .map(NumberType::Integer)
+            .ok_or(ArithmeticError::Overflow),
+        (left, right) => Ok(NumberType::Float(float(as_float(left), as_float(right)))),
+    }
+}
+
+// converting the integer to a float could round it, so the float is split into its integral
+// part, compared as an integer, and its fraction
+fn compare_mixed(int: i64, float: f64) -> Option<Ordering> {
+    if float.is_nan() {
+        return None;
+    }
+
+    if float >= i64::MAX as f64 {
+        return Some(Ordering::Less);
+    }
+
+    if float < i64::MIN as f64 {
+        return Some(Ordering::Greater);
+    }
+
+    let integral = float.trunc();
+
+    match int.cmp(&(integral as i64)) {
+        Ordering::Equal => 0.0.partial_cmp(&(float - integral)),
+        ordering => Some(ordering),
+    }
+}
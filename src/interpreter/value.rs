@@ -0,0 +1,154 @@
+use crate::interpreter::callable::LoxCallable;
+use crate::interpreter::class::{LoxClass, LoxInstance};
+use crate::interpreter::list::List;
+use crate::interpreter::map::Map;
+use crate::interpreter::module::Module;
+use crate::interpreter::native_class::NativeObject;
+use crate::interpreter::number;
+use crate::scanner::token_type::{Literal, NumberType};
+use std::any::Any;
+use std::cell::{Ref, RefCell, RefMut};
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+
+/// A value produced by evaluating Lox code. Everything but `nil`, booleans, numbers and strings is
+/// shared by reference, so every copy of one sees the same object.
+#[derive(Clone, Debug)]
+pub enum Value {
+    Nil,
+    Bool(bool),
+    Number(NumberType),
+    String(String),
+    List(Rc<RefCell<List<Value>>>),
+    Map(Rc<RefCell<Map<Value>>>),
+    Callable(Rc<dyn LoxCallable>),
+    Class(Rc<LoxClass>),
+    Instance(Rc<RefCell<LoxInstance>>),
+    Module(Rc<Module>),
+    /// An object of a class implemented in Rust.
+    Native(Rc<NativeObject>),
+}
+
+impl Value {
+    /// `nil` and `false` are falsey, everything else is truthy.
+    pub fn is_truthy(&self) -> bool {
+        !matches!(self, Value::Nil | Value::Bool(false))
+    }
+
+    /// The name of the value's type, as reported in error messages.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Nil => "nil",
+            Value::Bool(..) => "boolean",
+            Value::Number(..) => "number",
+            Value::String(..) => "string",
+            Value::List(..) => "list",
+            Value::Map(..) => "map",
+            Value::Callable(..) => "function",
+            Value::Class(..) => "class",
+            Value::Instance(..) => "instance",
+            Value::Module(..) => "module",
+            Value::Native(..) => "instance",
+        }
+    }
+
+    pub fn list(items: Vec<Value>) -> Self {
+        Value::List(Rc::new(RefCell::new(List::new(items))))
+    }
+
+    pub fn map(map: Map<Value>) -> Self {
+        Value::Map(Rc::new(RefCell::new(map)))
+    }
+
+    /// The Rust value behind an object of a native class, if it's a `T` and isn't being changed.
+    pub fn downcast<T: Any>(&self) -> Option<Ref<'_, T>> {
+        match self {
+            Value::Native(object) => object.downcast(),
+            _ => None,
+        }
+    }
+
+    /// Like `downcast`, to change the Rust value.
+    pub fn downcast_mut<T: Any>(&self) -> Option<RefMut<'_, T>> {
+        match self {
+            Value::Native(object) => object.downcast_mut(),
+            _ => None,
+        }
+    }
+
+    // how a value is written inside a list or map, where strings are quoted
+    fn write_nested(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::String(string) => write!(f, "{:?}", string),
+            value => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<Literal> for Value {
+    fn from(literal: Literal) -> Self {
+        match literal {
+            Literal::String(string) => Value::String(string),
+            Literal::Number(number) => Value::Number(number),
+            Literal::Bool(b) => Value::Bool(b),
+            Literal::Nil => Value::Nil,
+        }
+    }
+}
+
+// objects are only equal to themselves, the same as any other object
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Nil, Value::Nil) => true,
+            (Value::Bool(left), Value::Bool(right)) => left == right,
+            (Value::Number(left), Value::Number(right)) => number::equals(*left, *right),
+            (Value::String(left), Value::String(right)) => left == right,
+            (Value::List(left), Value::List(right)) => Rc::ptr_eq(left, right),
+            (Value::Map(left), Value::Map(right)) => Rc::ptr_eq(left, right),
+            (Value::Callable(left), Value::Callable(right)) => Rc::ptr_eq(left, right),
+            (Value::Class(left), Value::Class(right)) => Rc::ptr_eq(left, right),
+            (Value::Instance(left), Value::Instance(right)) => Rc::ptr_eq(left, right),
+            (Value::Module(left), Value::Module(right)) => Rc::ptr_eq(left, right),
+            (Value::Native(left), Value::Native(right)) => Rc::ptr_eq(left, right),
+            _ => false,
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Nil => write!(f, "nil"),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Number(number) => write!(f, "{}", number),
+            Value::String(string) => write!(f, "{}", string),
+            Value::List(list) => {
+                write!(f, "[")?;
+                for (i, item) in list.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    item.write_nested(f)?;
+                }
+                write!(f, "]")
+            }
+            Value::Map(map) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in map.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: ", key)?;
+                    value.write_nested(f)?;
+                }
+                write!(f, "}}")
+            }
+            Value::Callable(callable) => write!(f, "{}", callable.describe()),
+            Value::Class(class) => write!(f, "{}", class.name()),
+            Value::Instance(instance) => write!(f, "{} instance", instance.borrow().class().name()),
+            Value::Module(module) => write!(f, "<module {}>", module.name()),
+            Value::Native(object) => write!(f, "{} instance", object.class_name()),
+        }
+    }
+}
@@ -0,0 +1,21 @@
+//! A tree-walking interpreter for Lox. `Lox` runs scripts and lets Rust code share values and
+//! functions with them. The stages it's built from are public too, for tools that only need
+//! some of them.
+
+pub mod errors;
+pub mod expr;
+pub mod interpreter;
+mod lox;
+pub mod parser;
+pub mod resolver;
+pub mod scanner;
+pub mod serialize;
+pub mod stmt;
+
+pub use errors::{Error, ErrorType};
+pub use interpreter::convert::{FromValue, IntoValue};
+pub use interpreter::native_class::NativeClass;
+pub use interpreter::value::Value;
+pub use lox::Lox;
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,158 @@
+use crate::errors::{Error, ErrorType, RuntimeError};
+use crate::interpreter::convert::{FromValue, IntoValue};
+use crate::interpreter::interpreter::Interpreter;
+use crate::interpreter::limits::Limits;
+use crate::interpreter::native::NativeFunction;
+use crate::interpreter::native_class::NativeClass;
+use crate::interpreter::value::Value;
+use crate::parser::parser::Parser;
+use crate::resolver::resolver::Resolver;
+use crate::scanner::scanner::Scanner;
+use crate::stmt::Stmt;
+use crate::Result;
+use std::any::Any;
+use std::fs;
+use std::path::Path;
+
+/// A Lox interpreter to embed in a Rust program. Everything a run defines stays defined for the
+/// runs after it. Each call into it from Rust is one run, with the whole of the allowance set by
+/// `set_limits`.
+pub struct Lox {
+    interpreter: Interpreter,
+}
+
+impl Default for Lox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Lox {
+    pub fn new() -> Self {
+        Self {
+            interpreter: Interpreter::new(),
+        }
+    }
+
+    /// The interpreter underneath, for the settings `Lox` doesn't cover itself.
+    pub fn interpreter(&self) -> &Interpreter {
+        &self.interpreter
+    }
+
+    pub fn set_limits(&self, limits: Limits) {
+        self.interpreter.set_limits(limits);
+    }
+
+    /// Handles the resolver's warnings, for the script and the modules it imports. They're
+    /// dropped until this is set.
+    pub fn on_warning(&self, handler: impl Fn(&str) + 'static) {
+        self.interpreter.on_warning(handler);
+    }
+
+    /// Makes a Rust function callable from Lox as a global.
+    pub fn define_native(&self, native: NativeFunction) {
+        self.interpreter.define_native(native);
+    }
+
+    /// Makes a Rust type available to Lox as a class.
+    pub fn define_class(&self, class: NativeClass) {
+        self.interpreter.define_class(class);
+    }
+
+    /// Hands `data` to Lox as an object of the class defined for its type.
+    pub fn wrap<T: Any>(&self, data: T) -> Result<Value> {
+        self.interpreter.wrap(data)
+    }
+
+    /// Runs `source`, returning the value of its last statement if that's an expression, and nil
+    /// otherwise.
+    pub fn eval(&self, source: &str) -> Result<Value> {
+        let mut last = Value::Nil;
+        self.eval_each(source, |value| last = value.unwrap_or(Value::Nil))
+            .map_err(Error::completed)?;
+
+        Ok(last)
+    }
+
+    /// Runs `source` one statement at a time, handing `each` the value of every statement that's
+    /// an expression, and `None` for the rest. Source that ends inside an unclosed string or
+    /// bracket is an `Incomplete` error, so a prompt can wait for the rest of it.
+    pub fn eval_each(&self, source: &str, mut each: impl FnMut(Option<Value>)) -> Result<()> {
+        let statements = self.compile(source.as_bytes())?;
+
+        self.interpreter.run(|| {
+            for statement in statements {
+                match statement {
+                    Stmt::Expression(expression) => each(Some(
+                        self.interpreter
+                            .interpret_expression(&expression.expression)?,
+                    )),
+                    statement => {
+                        self.interpreter.interpret(&[statement])?;
+                        each(None);
+                    }
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Runs the script at `path`. Stack traces name it, and imports in it are found relative to
+    /// it.
+    pub fn run_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let source = fs::read(path).map_err(|err| Error(ErrorType::Io(err)))?;
+        let statements = self.compile(&source).map_err(Error::completed)?;
+
+        self.interpreter.set_file(&path.display().to_string());
+        self.interpreter.interpret(&statements)
+    }
+
+    /// Reads a global of the script, converted to `T`.
+    pub fn get_global<T: FromValue>(&self, name: &str) -> Result<T> {
+        let value = self
+            .interpreter
+            .global(name)
+            .ok_or_else(|| host_error(&format!("Undefined variable '{}'.", name)))?;
+
+        T::from_value(value).map_err(|message| host_error(&message))
+    }
+
+    /// Defines a global of the script, replacing any with the same name.
+    pub fn set_global(&self, name: &str, value: impl IntoValue) -> Result<()> {
+        self.interpreter.set_global(name, value.into_value())
+    }
+
+    /// Calls the global function or class `name`, converting what it returns to `T`.
+    pub fn call<T: FromValue>(&self, name: &str, arguments: Vec<Value>) -> Result<T> {
+        let function = self.get_global(name)?;
+
+        self.call_value(function, arguments)
+    }
+
+    /// Calls a Lox function, method or class, converting what it returns to `T`.
+    pub fn call_value<T: FromValue>(&self, function: Value, arguments: Vec<Value>) -> Result<T> {
+        let value = self.interpreter.call_value(function, arguments)?;
+
+        T::from_value(value).map_err(|message| host_error(&message))
+    }
+
+    // scans, parses and resolves source code, ready to be run
+    fn compile(&self, source: &[u8]) -> Result<Vec<Stmt>> {
+        let mut scanner = Scanner::new(source);
+        let tokens = scanner.scan_tokens()?;
+        let statements = Parser::new(tokens.to_vec()).parse()?;
+
+        for warning in Resolver::new().resolve(&statements)? {
+            self.interpreter.warn(&warning);
+        }
+
+        Ok(statements)
+    }
+}
+
+// a runtime error in a request from Rust, which has no place in the script to point at
+fn host_error(message: &str) -> Error {
+    Error(ErrorType::Interpreter(RuntimeError::new(message)))
+}
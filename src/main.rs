@@ -1,119 +1,160 @@
-mod errors;
-mod expr;
-mod parser;
-mod scanner;
-
-use crate::parser::parser::Parser;
-use errors::Error;
-use expr::{Binary, Expr, LiteralExpr, Unary};
-use scanner::pretty_printer::PrettyPrinter;
-use scanner::scanner::Scanner;
-use scanner::token_type::NumberType::{Float, Integer};
-use scanner::token_type::{SingleCharacter, TokenType};
-use scanner::{token, token_type};
-use std::fs::File;
+use rlox::interpreter::limits::Limits;
+use rlox::parser::parser::Parser;
+use rlox::scanner::scanner::Scanner;
+use rlox::serialize::AstFormat;
+use rlox::{Error, ErrorType, Lox, Result, Value};
 use std::io;
-use std::io::Read;
 use std::path::Path;
 use std::process::exit;
+use std::time::Duration;
 use std::{env, fs};
-use token::Token;
-
-type Result<T> = std::result::Result<T, Error>;
 
 fn main() -> Result<()> {
     let args: Vec<String> = env::args().collect();
 
-    let expression = Expr::Binary(Binary {
-        left: Box::new(Expr::Unary(Unary {
-            operator: Token::new(
-                TokenType::SingleCharacters(SingleCharacter::Minus),
-                "-",
-                None,
-                1,
-            ),
-            right: Box::new(Expr::Literal(LiteralExpr {
-                value: token_type::Literal::Number(Integer(123)),
-            })),
-        })),
-        operator: Token::new(
-            TokenType::SingleCharacters(SingleCharacter::Star),
-            "*",
-            None,
-            1,
-        ),
-        right: Box::new(Expr::Grouping(expr::GroupingExpr {
-            expression: Box::new(Expr::Literal(LiteralExpr {
-                value: token_type::Literal::Number(Float(45.67)),
-            })),
-        })),
-    });
-
-    let printer = PrettyPrinter::new();
-    printer.print_expr(&expression);
-
-    let x = match args.len() {
-        2 => run_file(&args[1]),
-        1 => run_prompt(),
-        _ => panic!("fucked up"),
-    }?;
+    let x = dispatch(&args)?;
 
     exit(x);
 }
 
-fn run_prompt() -> Result<i32> {
+fn dispatch(args: &[String]) -> Result<i32> {
+    let Some((limits, args)) = split_limits(&args[1..]) else {
+        return Ok(usage());
+    };
+
+    match args {
+        [flag, format, file_path] if flag == "--emit" => emit_ast(file_path, format.parse()?),
+        [file_path] => run_file(file_path, limits),
+        [] => run_prompt(limits),
+        _ => Ok(usage()),
+    }
+}
+
+fn usage() -> i32 {
+    println!("Usage: rlox [--max-steps n] [--timeout seconds] [--max-memory bytes] [--emit json|sexpr] [script]");
+    64
+}
+
+// takes the limit options off the front of the arguments. `None` when one has a bad value
+fn split_limits(args: &[String]) -> Option<(Limits, &[String])> {
+    let mut limits = Limits::default();
+    let mut args = args;
+
     loop {
-        println!(">");
-        let mut line = String::new();
-        io::stdin()
-            .read_line(&mut line)
+        match args {
+            [flag, steps, rest @ ..] if flag == "--max-steps" => {
+                limits.max_steps = Some(steps.parse().ok()?);
+                args = rest;
+            }
+            [flag, bytes, rest @ ..] if flag == "--max-memory" => {
+                limits.max_memory = Some(bytes.parse().ok()?);
+                args = rest;
+            }
+            [flag, seconds, rest @ ..] if flag == "--timeout" => {
+                limits.timeout = Some(Duration::try_from_secs_f64(seconds.parse().ok()?).ok()?);
+                args = rest;
+            }
+            _ => return Some((limits, args)),
+        }
+    }
+}
+
+// lines are collected until they form complete input, so a block or function can span several.
+// The value of each expression typed is echoed back
+fn run_prompt(limits: Limits) -> Result<i32> {
+    let lox = new_lox(limits);
+    let mut source = String::new();
+
+    loop {
+        println!("{}", if source.is_empty() { ">" } else { "..." });
+
+        let read = io::stdin()
+            .read_line(&mut source)
             .expect("failed to read line");
 
-        run(&line)?;
+        // input that's still incomplete when stdin closes is reported as it stands
+        if read == 0 {
+            if let Err(err) = lox.eval_each(&source, |_| {}) {
+                eprintln!("{}", err.completed());
+            }
+
+            return Ok(0);
+        }
+
+        let echo = |value: Option<Value>| {
+            if let Some(value) = value {
+                println!("{}", value);
+            }
+        };
+
+        match lox.eval_each(&source, echo) {
+            Err(Error(ErrorType::Incomplete(_))) => continue,
+            Err(err) => eprintln!("{}", err),
+            Ok(()) => {}
+        }
+
+        source.clear();
     }
 }
 
-fn run(line: &str) -> Result<()> {
-    let mut scanner = Scanner::new(line.as_bytes());
+fn run_file(file_path: &str, limits: Limits) -> Result<i32> {
+    if !Path::new(file_path).exists() {
+        return Ok(-1);
+    }
 
-    let tokens = scanner.scan_tokens();
-    let parser = Parser::new(tokens.to_vec());
+    match new_lox(limits).run_file(file_path) {
+        Ok(()) => Ok(0),
+        Err(err @ Error(ErrorType::Parse(_) | ErrorType::Scanner(_) | ErrorType::Resolve(_))) => {
+            eprintln!("{}", err);
+            Ok(65)
+        }
+        Err(err @ Error(ErrorType::Interpreter(_) | ErrorType::Limit(_))) => {
+            eprintln!("{}", err);
+            Ok(70)
+        }
+        Err(err) => Err(err),
+    }
+}
 
-    let printer = PrettyPrinter::new();
-    printer.print_expr(&parser.expression()?);
+// an interpreter that reports the resolver's warnings on stderr
+fn new_lox(limits: Limits) -> Lox {
+    let lox = Lox::new();
+    lox.set_limits(limits);
+    lox.on_warning(|warning| eprintln!("Warning `{}`", warning));
 
-    Ok(())
+    lox
 }
 
-fn run_file(file_path: &str) -> Result<i32> {
+// prints the syntax tree of a script, or converts a tree exported with `--emit` to another format.
+// Scripts with syntax errors still print their tree, with error nodes, and the errors go to stderr
+fn emit_ast(file_path: &str, format: AstFormat) -> Result<i32> {
     if !Path::new(file_path).exists() {
         return Ok(-1);
     }
 
     let buffer = read_source_file(file_path)?;
 
-    let mut scanner = Scanner::new(&buffer);
+    let (statements, errors) = match AstFormat::from_path(file_path) {
+        Some(input) => (input.read(&String::from_utf8_lossy(&buffer))?, vec![]),
+        None => {
+            let mut scanner = Scanner::new(&buffer);
+            let (tokens, mut errors) = scanner.scan_tokens_tolerant();
+            let (statements, parse_errors) = Parser::new(tokens.to_vec()).parse_tolerant()?;
+            errors.extend(parse_errors);
 
-    scanner.scan_tokens();
+            (statements, errors)
+        }
+    };
 
-    Ok(0)
-}
-
-//todo: error handling
-fn read_source_file(file_path: &str) -> Result<Vec<u8>> {
-    let mut file = File::open(file_path).unwrap();
-    let metadata = fs::metadata(file_path).unwrap();
-    let mut buffer = vec![0; metadata.len() as usize];
-
-    file.read_to_end(&mut buffer).expect("buffer overflow");
+    println!("{}", format.write(&statements)?);
 
-    Ok(buffer)
-}
+    for error in &errors {
+        eprintln!("{}", error);
+    }
 
-fn error(line: usize, message: &str) {
-    report(line, "", message);
+    Ok(if errors.is_empty() { 0 } else { 65 })
 }
 
-fn report(line: usize, where_err: &str, message: &str) {
-    println!("[Line {} ] Error {}: {}", line, where_err, message)
+fn read_source_file(file_path: &str) -> Result<Vec<u8>> {
+    fs::read(file_path).map_err(|err| Error(ErrorType::Io(err)))
 }
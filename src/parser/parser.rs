@@ -1,18 +1,36 @@
-use std::cell::RefCell;
-use std::fmt::format;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
-use crate::errors::ErrorType;
-use crate::expr::{Binary, Expr, GroupingExpr, LiteralExpr, Unary};
+use crate::errors::{Error, ErrorType};
+use crate::expr::{
+    Assign, Binary, Call, Comma, Conditional, ErrorExpr, Expr, FunctionExpr, Get, GroupingExpr,
+    Index, ListExpr, LiteralExpr, Logical, MapExpr, Set, SetIndex, Super, This, Unary, Variable,
+};
 use crate::scanner::token::Token;
 use crate::scanner::token_type::{Keyword, Literal, SingleCharacter, SingleOrDouble, TokenType};
+use crate::stmt::{
+    Block, Break, Catch, Class, Continue, ErrorStmt, Expression, Function, If, Import, Print,
+    Return, Stmt, Throw, Try, Var, While,
+};
 use crate::Result;
 
-pub struct Parser<'parser> {
-    tokens: Rc<RefCell<Vec<Token<'parser>>>>,
+pub struct Parser {
+    tokens: Rc<RefCell<Vec<Token>>>,
     current: Rc<RefCell<usize>>,
+    // how many loops enclose the statement being parsed, reset at every function boundary
+    loop_depth: Rc<RefCell<usize>>,
+    // errors that don't leave the parser confused, so they're recorded without unwinding
+    errors: Rc<RefCell<Vec<String>>>,
+    // set by the first error in a statement so the errors that cascade from it are dropped
+    panic_mode: Rc<RefCell<bool>>,
+    // how many blocks enclose the statement being parsed, so recovery doesn't skip their `}`
+    block_depth: Rc<RefCell<usize>>,
+    // whether the first error was found at the end of the input, where more input could fix it
+    error_at_end: Rc<RefCell<bool>>,
 }
 
+const MAX_ARGUMENTS: usize = 255;
+
 const EQUALITY_MATCH: [TokenType; 2] = [
     TokenType::SingleOrDoubles(SingleOrDouble::BangEqual),
     TokenType::SingleOrDoubles(SingleOrDouble::EqualEqual),
@@ -28,6 +46,11 @@ const ADD_AND_SUBTRACT_MATCH: [TokenType; 2] = [
     TokenType::SingleCharacters(SingleCharacter::Plus),
 ];
 
+const UNARY_MATCH: [TokenType; 2] = [
+    TokenType::SingleOrDoubles(SingleOrDouble::Bang),
+    TokenType::SingleCharacters(SingleCharacter::Minus),
+];
+
 const INEQUALITY_MATCH: [TokenType; 4] = [
     TokenType::SingleOrDoubles(SingleOrDouble::Greater),
     TokenType::SingleOrDoubles(SingleOrDouble::GreaterEqual),
@@ -35,16 +58,705 @@ const INEQUALITY_MATCH: [TokenType; 4] = [
     TokenType::SingleOrDoubles(SingleOrDouble::LessEqual),
 ];
 
-impl<'parser> Parser<'parser> {
-    pub fn new(tokens: Vec<Token<'parser>>) -> Self {
+// keywords that begin a statement, used to find a safe place to resume after an error
+const STATEMENT_START: [Keyword; 11] = [
+    Keyword::Class,
+    Keyword::Fun,
+    Keyword::Var,
+    Keyword::For,
+    Keyword::If,
+    Keyword::While,
+    Keyword::Print,
+    Keyword::Return,
+    Keyword::Throw,
+    Keyword::Try,
+    Keyword::Import,
+];
+
+impl Parser {
+    pub fn new(tokens: Vec<Token>) -> Self {
         Self {
             tokens: Rc::new(RefCell::new(tokens)),
             current: Rc::new(RefCell::new(0)),
+            loop_depth: Rc::new(RefCell::new(0)),
+            errors: Rc::new(RefCell::new(vec![])),
+            panic_mode: Rc::new(RefCell::new(false)),
+            block_depth: Rc::new(RefCell::new(0)),
+            error_at_end: Rc::new(RefCell::new(false)),
+        }
+    }
+
+    pub fn parse(&self) -> Result<Vec<Stmt>> {
+        let (statements, errors) = self.parse_tolerant()?;
+
+        if !errors.is_empty() && *self.error_at_end.borrow() && self.has_unclosed_delimiter() {
+            return Err(Error(ErrorType::Incomplete(Box::new(Error(
+                ErrorType::Parse(errors.join("\n")),
+            )))));
+        }
+
+        if !errors.is_empty() {
+            return Err(Error(ErrorType::Parse(errors.join("\n"))));
+        }
+
+        Ok(statements)
+    }
+
+    /// Parses as much as it can, for editor tooling. Broken input becomes `Stmt::Error` and
+    /// `Expr::Error` nodes in an otherwise complete tree, returned alongside the error messages.
+    pub fn parse_tolerant(&self) -> Result<(Vec<Stmt>, Vec<String>)> {
+        let mut statements = vec![];
+
+        while !self.is_end() {
+            statements.push(self.declaration()?);
+        }
+
+        Ok((statements, self.errors.take()))
+    }
+
+    // a statement that failed to parse is replaced by an error node spanning the tokens skipped
+    // while recovering, so only non-parse errors are ever returned from here
+    fn declaration(&self) -> Result<Stmt> {
+        let start = *self.current.borrow();
+
+        match self.declaration_kind() {
+            Ok(statement) => {
+                self.panic_mode.replace(false);
+
+                Ok(statement)
+            }
+            Err(Error(ErrorType::Parse(message))) => {
+                self.record(message.clone());
+                self.synchronize();
+                self.panic_mode.replace(false);
+
+                Ok(Stmt::Error(ErrorStmt {
+                    tokens: self.tokens_from(start),
+                    message,
+                }))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn declaration_kind(&self) -> Result<Stmt> {
+        if self.does_match(vec![TokenType::Keywords(Keyword::Class)]) {
+            return self.class_declaration();
+        }
+
+        // `fun` without a name starts an anonymous function, which is an expression statement
+        if self.check(TokenType::Keywords(Keyword::Fun)) && self.check_next(TokenType::Identifier) {
+            self.advance();
+
+            return Ok(Stmt::Function(self.function("function")?));
+        }
+
+        if self.does_match(vec![TokenType::Keywords(Keyword::Var)]) {
+            return self.var_declaration();
+        }
+
+        if self.does_match(vec![TokenType::Keywords(Keyword::Import)]) {
+            return self.import_declaration();
+        }
+
+        self.statement()
+    }
+
+    fn class_declaration(&self) -> Result<Stmt> {
+        let name = self.consume(TokenType::Identifier, "Expect class name.")?;
+
+        let superclass = if self.does_match(vec![TokenType::SingleOrDoubles(SingleOrDouble::Less)])
+        {
+            let name = self.consume(TokenType::Identifier, "Expect superclass name.")?;
+
+            Some(Variable {
+                name,
+                depth: Cell::default(),
+            })
+        } else {
+            None
+        };
+
+        self.consume(
+            TokenType::SingleCharacters(SingleCharacter::LeftBrace),
+            "Expect '{' before class body.",
+        )?;
+
+        let mut methods = vec![];
+        while !self.check(TokenType::SingleCharacters(SingleCharacter::RightBrace))
+            && !self.is_end()
+        {
+            methods.push(self.function("method")?);
+        }
+
+        self.consume(
+            TokenType::SingleCharacters(SingleCharacter::RightBrace),
+            "Expect '}' after class body.",
+        )?;
+
+        Ok(Stmt::Class(Class {
+            name,
+            superclass,
+            methods,
+        }))
+    }
+
+    fn function(&self, kind: &str) -> Result<Function> {
+        let name = self.consume(TokenType::Identifier, &format!("Expect {} name.", kind))?;
+
+        self.consume(
+            TokenType::SingleCharacters(SingleCharacter::LeftParen),
+            &format!("Expect '(' after {} name.", kind),
+        )?;
+
+        let (params, body) = self.function_body(kind)?;
+
+        Ok(Function { name, params, body })
+    }
+
+    // parameters and body shared by named declarations and anonymous functions, starting just
+    // after the opening '('
+    fn function_body(&self, kind: &str) -> Result<(Vec<Token>, Rc<Vec<Stmt>>)> {
+        let paren = self.previous();
+
+        // a broken parameter list is skipped up to the body, so the function is still declared
+        let params = match self.parameters(&paren) {
+            Ok(params) => params,
+            Err(Error(ErrorType::Parse(message))) => {
+                self.record(message);
+                self.skip_to_body();
+                if self.check(TokenType::SingleCharacters(SingleCharacter::LeftBrace)) {
+                    self.panic_mode.replace(false);
+                }
+
+                vec![]
+            }
+            Err(err) => return Err(err),
+        };
+
+        self.consume(
+            TokenType::SingleCharacters(SingleCharacter::LeftBrace),
+            &format!("Expect '{{' before {} body.", kind),
+        )?;
+
+        // a loop around the declaration doesn't make `break` valid inside its body
+        let enclosing_loops = self.loop_depth.replace(0);
+        let body = self.block();
+        self.loop_depth.replace(enclosing_loops);
+
+        Ok((params, Rc::new(body?)))
+    }
+
+    fn parameters(&self, paren: &Token) -> Result<Vec<Token>> {
+        let mut params = vec![];
+        if !self.check(TokenType::SingleCharacters(SingleCharacter::RightParen)) {
+            loop {
+                if params.len() >= MAX_ARGUMENTS {
+                    return Err(self.error(&self.peek(), "Can't have more than 255 parameters."));
+                }
+
+                params.push(self.consume(TokenType::Identifier, "Expect parameter name.")?);
+
+                if !self.does_match(vec![TokenType::SingleCharacters(SingleCharacter::Comma)]) {
+                    break;
+                }
+            }
+        }
+
+        self.consume_closing(
+            paren,
+            SingleCharacter::RightParen,
+            "Expect ')' after parameters.",
+        )?;
+
+        Ok(params)
+    }
+
+    // skips to the `{` of a function body, stopping at the end of the statement if there isn't one
+    fn skip_to_body(&self) {
+        while !self.is_end()
+            && !self.check(TokenType::SingleCharacters(SingleCharacter::LeftBrace))
+            && !self.check(TokenType::SingleCharacters(SingleCharacter::Semicolon))
+        {
+            self.advance();
+        }
+    }
+
+    // `as` is only special here, so it isn't a keyword
+    fn import_declaration(&self) -> Result<Stmt> {
+        let keyword = self.previous();
+
+        let path = match self
+            .consume(
+                TokenType::Keywords(Keyword::String),
+                "Expect module path after 'import'.",
+            )?
+            .literal()
+        {
+            Some(Literal::String(path)) => path,
+            _ => String::new(),
+        };
+
+        let as_keyword = self.consume(TokenType::Identifier, "Expect 'as' after module path.")?;
+        if as_keyword.lexeme() != "as" {
+            self.report(&as_keyword, "Expect 'as' after module path.");
+        }
+
+        let name = self.consume(TokenType::Identifier, "Expect module name.")?;
+
+        self.consume_semicolon("Expect ';' after import.")?;
+
+        Ok(Stmt::Import(Import {
+            keyword,
+            path,
+            name,
+        }))
+    }
+
+    fn var_declaration(&self) -> Result<Stmt> {
+        let name = self.consume(TokenType::Identifier, "Expect variable name.")?;
+
+        let initializer =
+            if self.does_match(vec![TokenType::SingleOrDoubles(SingleOrDouble::Equal)]) {
+                Some(self.expression()?)
+            } else {
+                None
+            };
+
+        self.consume_semicolon("Expect ';' after variable declaration.")?;
+
+        Ok(Stmt::Var(Var { name, initializer }))
+    }
+
+    fn statement(&self) -> Result<Stmt> {
+        if self.does_match(vec![TokenType::Keywords(Keyword::Break)]) {
+            return self.break_statement();
+        }
+
+        if self.does_match(vec![TokenType::Keywords(Keyword::Continue)]) {
+            return self.continue_statement();
+        }
+
+        if self.does_match(vec![TokenType::Keywords(Keyword::For)]) {
+            return self.for_statement();
+        }
+
+        if self.does_match(vec![TokenType::Keywords(Keyword::If)]) {
+            return self.if_statement();
+        }
+
+        if self.does_match(vec![TokenType::Keywords(Keyword::Print)]) {
+            return self.print_statement();
+        }
+
+        if self.does_match(vec![TokenType::Keywords(Keyword::Return)]) {
+            return self.return_statement();
+        }
+
+        if self.does_match(vec![TokenType::Keywords(Keyword::Throw)]) {
+            return self.throw_statement();
+        }
+
+        if self.does_match(vec![TokenType::Keywords(Keyword::Try)]) {
+            return self.try_statement();
+        }
+
+        if self.does_match(vec![TokenType::Keywords(Keyword::While)]) {
+            return self.while_statement();
+        }
+
+        if self.does_match(vec![TokenType::SingleCharacters(
+            SingleCharacter::LeftBrace,
+        )]) {
+            return Ok(Stmt::Block(Block {
+                statements: self.block()?,
+            }));
+        }
+
+        self.expression_statement()
+    }
+
+    fn break_statement(&self) -> Result<Stmt> {
+        let keyword = self.loop_keyword();
+
+        self.consume_semicolon("Expect ';' after 'break'.")?;
+
+        Ok(Stmt::Break(Break { keyword }))
+    }
+
+    fn continue_statement(&self) -> Result<Stmt> {
+        let keyword = self.loop_keyword();
+
+        self.consume_semicolon("Expect ';' after 'continue'.")?;
+
+        Ok(Stmt::Continue(Continue { keyword }))
+    }
+
+    // the `break` or `continue` just matched, reporting it if there's no loop around it
+    fn loop_keyword(&self) -> Token {
+        let keyword = self.previous();
+
+        if *self.loop_depth.borrow() == 0 {
+            self.report(
+                &keyword,
+                &format!("Can't use '{}' outside of a loop.", keyword.lexeme()),
+            );
         }
+
+        keyword
+    }
+
+    fn loop_body(&self) -> Result<Stmt> {
+        *self.loop_depth.borrow_mut() += 1;
+        let body = self.statement();
+        *self.loop_depth.borrow_mut() -= 1;
+
+        body
+    }
+
+    // a `for` loop is desugared into a `while` loop wrapped in blocks for the initializer
+    fn for_statement(&self) -> Result<Stmt> {
+        let paren = self.consume(
+            TokenType::SingleCharacters(SingleCharacter::LeftParen),
+            "Expect '(' after 'for'.",
+        )?;
+
+        let initializer = if self.does_match(vec![TokenType::SingleCharacters(
+            SingleCharacter::Semicolon,
+        )]) {
+            None
+        } else if self.does_match(vec![TokenType::Keywords(Keyword::Var)]) {
+            Some(self.var_declaration()?)
+        } else {
+            Some(self.expression_statement()?)
+        };
+
+        let condition = if self.check(TokenType::SingleCharacters(SingleCharacter::Semicolon)) {
+            None
+        } else {
+            Some(self.condition()?)
+        };
+
+        self.consume(
+            TokenType::SingleCharacters(SingleCharacter::Semicolon),
+            "Expect ';' after loop condition.",
+        )?;
+
+        let increment = if self.check(TokenType::SingleCharacters(SingleCharacter::RightParen)) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+
+        self.consume_closing(
+            &paren,
+            SingleCharacter::RightParen,
+            "Expect ')' after for clauses.",
+        )?;
+
+        let mut body = Stmt::While(While {
+            condition: condition.unwrap_or(Expr::Literal(LiteralExpr {
+                value: Literal::Bool(true),
+            })),
+            body: Box::new(self.loop_body()?),
+            increment,
+        });
+
+        if let Some(initializer) = initializer {
+            body = Stmt::Block(Block {
+                statements: vec![initializer, body],
+            });
+        }
+
+        Ok(body)
+    }
+
+    fn if_statement(&self) -> Result<Stmt> {
+        let paren = self.consume(
+            TokenType::SingleCharacters(SingleCharacter::LeftParen),
+            "Expect '(' after 'if'.",
+        )?;
+
+        let condition = self.condition()?;
+
+        self.consume_closing(
+            &paren,
+            SingleCharacter::RightParen,
+            "Expect ')' after if condition.",
+        )?;
+
+        let then_branch = Box::new(self.statement()?);
+        let else_branch = if self.does_match(vec![TokenType::Keywords(Keyword::Else)]) {
+            Some(Box::new(self.statement()?))
+        } else {
+            None
+        };
+
+        Ok(Stmt::If(If {
+            condition,
+            then_branch,
+            else_branch,
+        }))
+    }
+
+    fn print_statement(&self) -> Result<Stmt> {
+        let expression = self.expression()?;
+
+        self.consume_semicolon("Expect ';' after value.")?;
+
+        Ok(Stmt::Print(Print { expression }))
+    }
+
+    fn return_statement(&self) -> Result<Stmt> {
+        let keyword = self.previous();
+
+        let value = if self.check(TokenType::SingleCharacters(SingleCharacter::Semicolon)) {
+            None
+        } else {
+            Some(self.expression()?)
+        };
+
+        self.consume_semicolon("Expect ';' after return value.")?;
+
+        Ok(Stmt::Return(Return { keyword, value }))
+    }
+
+    fn throw_statement(&self) -> Result<Stmt> {
+        let keyword = self.previous();
+        let value = self.expression()?;
+
+        self.consume_semicolon("Expect ';' after thrown value.")?;
+
+        Ok(Stmt::Throw(Throw { keyword, value }))
+    }
+
+    fn try_statement(&self) -> Result<Stmt> {
+        let keyword = self.previous();
+
+        self.consume(
+            TokenType::SingleCharacters(SingleCharacter::LeftBrace),
+            "Expect '{' after 'try'.",
+        )?;
+        let body = self.block()?;
+
+        let catch = if self.does_match(vec![TokenType::Keywords(Keyword::Catch)]) {
+            let paren = self.consume(
+                TokenType::SingleCharacters(SingleCharacter::LeftParen),
+                "Expect '(' after 'catch'.",
+            )?;
+            let name = self.consume(TokenType::Identifier, "Expect error variable name.")?;
+            self.consume_closing(
+                &paren,
+                SingleCharacter::RightParen,
+                "Expect ')' after error variable.",
+            )?;
+
+            self.consume(
+                TokenType::SingleCharacters(SingleCharacter::LeftBrace),
+                "Expect '{' before catch body.",
+            )?;
+
+            Some(Catch {
+                name,
+                body: self.block()?,
+            })
+        } else {
+            None
+        };
+
+        let finally = if self.does_match(vec![TokenType::Keywords(Keyword::Finally)]) {
+            self.consume(
+                TokenType::SingleCharacters(SingleCharacter::LeftBrace),
+                "Expect '{' after 'finally'.",
+            )?;
+
+            Some(self.block()?)
+        } else {
+            None
+        };
+
+        if catch.is_none() && finally.is_none() {
+            self.report(&keyword, "Expect 'catch' or 'finally' after try block.");
+        }
+
+        Ok(Stmt::Try(Try {
+            keyword,
+            body,
+            catch,
+            finally,
+        }))
+    }
+
+    fn while_statement(&self) -> Result<Stmt> {
+        let paren = self.consume(
+            TokenType::SingleCharacters(SingleCharacter::LeftParen),
+            "Expect '(' after 'while'.",
+        )?;
+
+        let condition = self.condition()?;
+
+        self.consume_closing(
+            &paren,
+            SingleCharacter::RightParen,
+            "Expect ')' after condition.",
+        )?;
+
+        let body = Box::new(self.loop_body()?);
+
+        Ok(Stmt::While(While {
+            condition,
+            body,
+            increment: None,
+        }))
+    }
+
+    // expects the opening '{' to have just been consumed
+    fn block(&self) -> Result<Vec<Stmt>> {
+        let brace = self.previous();
+        let mut statements = vec![];
+
+        *self.block_depth.borrow_mut() += 1;
+        while !self.check(TokenType::SingleCharacters(SingleCharacter::RightBrace))
+            && !self.is_end()
+        {
+            match self.declaration() {
+                Ok(statement) => statements.push(statement),
+                Err(err) => {
+                    *self.block_depth.borrow_mut() -= 1;
+
+                    return Err(err);
+                }
+            }
+        }
+        *self.block_depth.borrow_mut() -= 1;
+
+        self.consume_closing(
+            &brace,
+            SingleCharacter::RightBrace,
+            "Expect '}' after block.",
+        )?;
+
+        Ok(statements)
+    }
+
+    fn expression_statement(&self) -> Result<Stmt> {
+        let expression = self.expression()?;
+
+        self.consume_semicolon("Expect ';' after expression.")?;
+
+        Ok(Stmt::Expression(Expression { expression }))
     }
 
     pub fn expression(&self) -> Result<Expr> {
-        self.equality()
+        self.comma()
+    }
+
+    // the comma operator binds loosest of all, so anywhere a single value is expected (call
+    // arguments for example) parsing starts at `assignment` instead
+    fn comma(&self) -> Result<Expr> {
+        let mut expr = self.assignment()?;
+
+        while self.does_match(vec![TokenType::SingleCharacters(SingleCharacter::Comma)]) {
+            let operator = self.previous();
+            let right = self.assignment()?;
+
+            expr = Expr::Comma(Comma {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            });
+        }
+
+        Ok(expr)
+    }
+
+    fn assignment(&self) -> Result<Expr> {
+        let expr = self.conditional()?;
+
+        if self.does_match(vec![TokenType::SingleOrDoubles(SingleOrDouble::Equal)]) {
+            let equals = self.previous();
+            let value = Box::new(self.assignment()?);
+
+            return match expr {
+                Expr::Variable(variable) => Ok(Expr::Assign(Assign {
+                    name: variable.name,
+                    value,
+                    depth: Cell::default(),
+                })),
+                Expr::Get(get) => Ok(Expr::Set(Set {
+                    object: get.object,
+                    name: get.name,
+                    value,
+                })),
+                Expr::Index(index) => Ok(Expr::SetIndex(SetIndex {
+                    object: index.object,
+                    bracket: index.bracket,
+                    index: index.index,
+                    value,
+                })),
+                _ => Err(self.error(&equals, "Invalid assignment target.")),
+            };
+        }
+
+        Ok(expr)
+    }
+
+    // the else branch recurses into `conditional` so `a ? b : c ? d : e` nests to the right
+    fn conditional(&self) -> Result<Expr> {
+        let condition = self.or()?;
+
+        if self.does_match(vec![TokenType::SingleCharacters(SingleCharacter::Question)]) {
+            let then_branch = self.expression()?;
+
+            self.consume(
+                TokenType::SingleCharacters(SingleCharacter::Colon),
+                "Expect ':' after then branch of conditional expression.",
+            )?;
+
+            let else_branch = self.conditional()?;
+
+            return Ok(Expr::Conditional(Conditional {
+                condition: Box::new(condition),
+                then_branch: Box::new(then_branch),
+                else_branch: Box::new(else_branch),
+            }));
+        }
+
+        Ok(condition)
+    }
+
+    fn or(&self) -> Result<Expr> {
+        let mut expr = self.and()?;
+
+        while self.does_match(vec![TokenType::Keywords(Keyword::Or)]) {
+            let operator = self.previous();
+            let right = self.and()?;
+
+            expr = Expr::Logical(Logical {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            });
+        }
+
+        Ok(expr)
+    }
+
+    fn and(&self) -> Result<Expr> {
+        let mut expr = self.equality()?;
+
+        while self.does_match(vec![TokenType::Keywords(Keyword::And)]) {
+            let operator = self.previous();
+            let right = self.equality()?;
+
+            expr = Expr::Logical(Logical {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            });
+        }
+
+        Ok(expr)
     }
 
     fn equality(&self) -> Result<Expr> {
@@ -126,7 +838,7 @@ impl<'parser> Parser<'parser> {
     }
 
     fn unary(&self) -> Result<Expr> {
-        if self.does_match(Vec::from(ADD_AND_SUBTRACT_MATCH)) {
+        if self.does_match(Vec::from(UNARY_MATCH)) {
             let operator = self.previous();
 
             return Ok(Expr::Unary(Unary {
@@ -135,7 +847,78 @@ impl<'parser> Parser<'parser> {
             }));
         }
 
-        self.primary()
+        self.call()
+    }
+
+    fn call(&self) -> Result<Expr> {
+        let mut expr = self.primary()?;
+
+        loop {
+            if self.does_match(vec![TokenType::SingleCharacters(
+                SingleCharacter::LeftParen,
+            )]) {
+                expr = self.finish_call(expr)?;
+            } else if self.does_match(vec![TokenType::SingleCharacters(SingleCharacter::Dot)]) {
+                let name =
+                    self.consume(TokenType::Identifier, "Expect property name after '.'.")?;
+
+                expr = Expr::Get(Get {
+                    object: Box::new(expr),
+                    name,
+                });
+            } else if self.does_match(vec![TokenType::SingleCharacters(
+                SingleCharacter::LeftBracket,
+            )]) {
+                let open = self.previous();
+                let index = self.expression()?;
+                let bracket = self.consume_closing(
+                    &open,
+                    SingleCharacter::RightBracket,
+                    "Expect ']' after index.",
+                )?;
+
+                expr = Expr::Index(Index {
+                    object: Box::new(expr),
+                    bracket,
+                    index: Box::new(index),
+                });
+            } else {
+                break;
+            }
+        }
+
+        Ok(expr)
+    }
+
+    fn finish_call(&self, callee: Expr) -> Result<Expr> {
+        let paren = self.previous();
+        let mut arguments = vec![];
+
+        if !self.check(TokenType::SingleCharacters(SingleCharacter::RightParen)) {
+            loop {
+                if arguments.len() >= MAX_ARGUMENTS {
+                    return Err(self.error(&self.peek(), "Can't have more than 255 arguments."));
+                }
+
+                arguments.push(self.assignment()?);
+
+                if !self.does_match(vec![TokenType::SingleCharacters(SingleCharacter::Comma)]) {
+                    break;
+                }
+            }
+        }
+
+        let parenthesis = self.consume_closing(
+            &paren,
+            SingleCharacter::RightParen,
+            "Expect ')' after arguments.",
+        )?;
+
+        Ok(Expr::Call(Call {
+            callee: Box::new(callee),
+            parenthesis,
+            arguments,
+        }))
     }
 
     fn check(&self, token_type: TokenType) -> bool {
@@ -146,6 +929,13 @@ impl<'parser> Parser<'parser> {
         }
     }
 
+    fn check_next(&self, token_type: TokenType) -> bool {
+        match self.tokens.borrow().get(*self.current.borrow() + 1) {
+            Some(token) => token_type == token.token_type(),
+            None => false,
+        }
+    }
+
     fn primary(&self) -> Result<Expr> {
         if self.does_match(vec![TokenType::Keywords(Keyword::False)]) {
             return Ok(Expr::Literal(LiteralExpr {
@@ -175,38 +965,234 @@ impl<'parser> Parser<'parser> {
             }));
         }
 
+        if self.does_match(vec![TokenType::Keywords(Keyword::Super)]) {
+            let keyword = self.previous();
+
+            self.consume(
+                TokenType::SingleCharacters(SingleCharacter::Dot),
+                "Expect '.' after 'super'.",
+            )?;
+
+            let method = self.consume(TokenType::Identifier, "Expect superclass method name.")?;
+
+            return Ok(Expr::Super(Super {
+                keyword,
+                method,
+                depth: Cell::default(),
+            }));
+        }
+
+        if self.does_match(vec![TokenType::SingleCharacters(
+            SingleCharacter::LeftBracket,
+        )]) {
+            return self.list();
+        }
+
+        // statements check for blocks before ever reaching here, so a brace in expression
+        // position can only start a map
+        if self.does_match(vec![TokenType::SingleCharacters(
+            SingleCharacter::LeftBrace,
+        )]) {
+            return self.map();
+        }
+
+        if self.does_match(vec![TokenType::Keywords(Keyword::Fun)]) {
+            let keyword = self.previous();
+
+            self.consume(
+                TokenType::SingleCharacters(SingleCharacter::LeftParen),
+                "Expect '(' after 'fun'.",
+            )?;
+
+            let (params, body) = self.function_body("function")?;
+
+            return Ok(Expr::Function(FunctionExpr {
+                keyword,
+                params,
+                body,
+            }));
+        }
+
+        if self.does_match(vec![TokenType::Keywords(Keyword::This)]) {
+            return Ok(Expr::This(This {
+                keyword: self.previous(),
+                depth: Cell::default(),
+            }));
+        }
+
+        if self.does_match(vec![TokenType::Identifier]) {
+            return Ok(Expr::Variable(Variable {
+                name: self.previous(),
+                depth: Cell::default(),
+            }));
+        }
+
         if self.does_match(vec![TokenType::SingleCharacters(
             SingleCharacter::LeftParen,
         )]) {
+            let paren = self.previous();
             let expr = self.expression()?;
 
-            self.consume(SingleCharacter::RightParen, r"Expect ')' after expression")?;
+            self.consume_closing(
+                &paren,
+                SingleCharacter::RightParen,
+                "Expect ')' after expression.",
+            )?;
 
             return Ok(Expr::Grouping(GroupingExpr {
                 expression: Box::new(expr),
             }));
         }
 
-        Err(crate::errors::Error(ErrorType::Parse(String::from(
-            "Expected expression.",
-        ))))
+        // the scanner has already reported the input it couldn't make a token of, so it counts
+        // as the statement's first error
+        if self.does_match(vec![TokenType::Error]) {
+            self.panic_mode.replace(true);
+
+            let token = self.previous();
+            let message = match token.literal() {
+                Some(Literal::String(message)) => message,
+                _ => String::new(),
+            };
+
+            return Ok(Expr::Error(ErrorExpr {
+                tokens: vec![token],
+                message,
+            }));
+        }
+
+        if let Some(error) = self.missing_left_operand()? {
+            return Ok(error);
+        }
+
+        // a token that can't start an expression is swallowed into an error node. One that
+        // closes something is left for what it closes, so `var x = ;` still declares `x`, and
+        // at the start of a statement or the end of input the statement is abandoned instead
+        let token = self.peek();
+        if self.is_end() || starts_statement(&token) {
+            return Err(self.error(&token, "Expect expression."));
+        }
+
+        let message = self.build_parser_error_msg(&token, "Expect expression.");
+        self.record(message.clone());
+
+        let tokens = if is_closing(&token) {
+            vec![]
+        } else {
+            vec![self.advance()]
+        };
+
+        Ok(Expr::Error(ErrorExpr { tokens, message }))
+    }
+
+    // error production for a binary operator with nothing on its left, e.g. `* 3`. The right
+    // operand is still parsed, at the operator's precedence, so parsing resumes after it
+    fn missing_left_operand(&self) -> Result<Option<Expr>> {
+        let start = *self.current.borrow();
+        let operator = self.peek();
+
+        let right_operand: fn(&Self) -> Result<Expr> = match operator.token_type() {
+            TokenType::Keywords(Keyword::Or) => Self::and,
+            TokenType::Keywords(Keyword::And) => Self::equality,
+            TokenType::SingleOrDoubles(SingleOrDouble::BangEqual | SingleOrDouble::EqualEqual) => {
+                Self::comparison
+            }
+            TokenType::SingleOrDoubles(
+                SingleOrDouble::Greater
+                | SingleOrDouble::GreaterEqual
+                | SingleOrDouble::Less
+                | SingleOrDouble::LessEqual,
+            ) => Self::term,
+            TokenType::SingleCharacters(SingleCharacter::Plus) => Self::factor,
+            TokenType::SingleCharacters(SingleCharacter::Slash | SingleCharacter::Star) => {
+                Self::unary
+            }
+            _ => return Ok(None),
+        };
+
+        self.advance();
+        right_operand(self)?;
+
+        let message = self.build_parser_error_msg(
+            &operator,
+            &format!("Missing left-hand operand before '{}'.", operator.lexeme()),
+        );
+        self.record(message.clone());
+
+        Ok(Some(Expr::Error(ErrorExpr {
+            tokens: self.tokens_from(start),
+            message,
+        })))
+    }
+
+    fn list(&self) -> Result<Expr> {
+        let bracket = self.previous();
+        let mut elements = vec![];
+
+        if !self.check(TokenType::SingleCharacters(SingleCharacter::RightBracket)) {
+            loop {
+                elements.push(self.assignment()?);
+
+                if !self.does_match(vec![TokenType::SingleCharacters(SingleCharacter::Comma)]) {
+                    break;
+                }
+            }
+        }
+
+        self.consume_closing(
+            &bracket,
+            SingleCharacter::RightBracket,
+            "Expect ']' after list elements.",
+        )?;
+
+        Ok(Expr::List(ListExpr { bracket, elements }))
+    }
+
+    fn map(&self) -> Result<Expr> {
+        let brace = self.previous();
+        let mut entries = vec![];
+
+        if !self.check(TokenType::SingleCharacters(SingleCharacter::RightBrace)) {
+            loop {
+                let key = self.assignment()?;
+
+                self.consume(
+                    TokenType::SingleCharacters(SingleCharacter::Colon),
+                    "Expect ':' after map key.",
+                )?;
+
+                entries.push((key, self.assignment()?));
+
+                if !self.does_match(vec![TokenType::SingleCharacters(SingleCharacter::Comma)]) {
+                    break;
+                }
+            }
+        }
+
+        self.consume_closing(
+            &brace,
+            SingleCharacter::RightBrace,
+            "Expect '}' after map entries.",
+        )?;
+
+        Ok(Expr::Map(MapExpr { brace, entries }))
     }
 
-    fn advance(&self) -> Result<Token> {
+    fn advance(&self) -> Token {
         if !self.is_end() {
             let new_val = self.current.borrow().to_owned() + 1;
             self.current.replace(new_val);
         }
 
-        Ok(self.previous())
+        self.previous()
     }
 
-    fn previous(&self) -> Token<'parser> {
-        *self
-            .tokens
+    fn previous(&self) -> Token {
+        self.tokens
             .borrow()
             .get(*(self.current.borrow()) - 1)
             .expect("unable to get previous")
+            .to_owned()
     }
 
     fn is_end(&self) -> bool {
@@ -221,43 +1207,175 @@ impl<'parser> Parser<'parser> {
             .to_owned()
     }
 
-    fn consume(&self, token_type: SingleCharacter, message: &str) -> Result<()> {
-        if self.check(TokenType::SingleCharacters(token_type)) {
-            self.advance()?;
+    fn consume(&self, token_type: TokenType, message: &str) -> Result<Token> {
+        if self.check(token_type) {
+            return Ok(self.advance());
+        }
+
+        Err(self.error(&self.peek(), message))
+    }
 
-            return Ok(());
+    // a statement's terminating ';'. When it's missing the error points at the end of the
+    // statement rather than at whatever comes next, which is often on a later line
+    fn consume_semicolon(&self, message: &str) -> Result<Token> {
+        if self.check(TokenType::SingleCharacters(SingleCharacter::Semicolon)) {
+            return Ok(self.advance());
         }
 
-        Err(crate::errors::Error(ErrorType::Parse(String::from(
-            self.build_parser_error_msg(message),
-        ))))
+        Err(self.error(&self.previous(), message))
     }
 
-    fn build_parser_error_msg(&self, message: &str) -> String {
-        let token = self.peek();
+    // the closing half of a delimiter pair. When it's missing the error points back at the
+    // unclosed `open` token, and says where parsing stopped
+    fn consume_closing(
+        &self,
+        open: &Token,
+        closing: SingleCharacter,
+        message: &str,
+    ) -> Result<Token> {
+        if self.check(TokenType::SingleCharacters(closing)) {
+            return Ok(self.advance());
+        }
+
+        let found = self.peek();
+        let found = match found.token_type() {
+            TokenType::Keywords(Keyword::Eof) => String::from("end of input"),
+            _ => format!("'{}' on line {}", found.lexeme(), found.line()),
+        };
+
+        Err(self.error(
+            open,
+            &format!(
+                "Unclosed '{}'. {} Found {} instead.",
+                open.lexeme(),
+                message,
+                found
+            ),
+        ))
+    }
+
+    // an `if`, `while` or `for` condition. Assigning at the top of one is almost always a
+    // mistyped `==`, so it's reported, though parsing carries on normally
+    fn condition(&self) -> Result<Expr> {
+        let condition = self.expression()?;
+
+        let target = match &condition {
+            Expr::Assign(assign) => Some(&assign.name),
+            Expr::Set(set) => Some(&set.name),
+            Expr::SetIndex(set) => Some(&set.bracket),
+            _ => None,
+        };
+
+        if let Some(target) = target {
+            self.report(
+                target,
+                "Use '==' to compare values in a condition, '=' assigns.",
+            );
+        }
+
+        Ok(condition)
+    }
+
+    fn report(&self, token: &Token, message: &str) {
+        self.record(self.build_parser_error_msg(token, message));
+    }
+
+    // only the first error of a statement is kept, later ones are usually fallout from it
+    fn record(&self, message: String) {
+        if !self.panic_mode.replace(true) {
+            if self.errors.borrow().is_empty() {
+                self.error_at_end.replace(self.is_end());
+            }
+
+            self.errors.borrow_mut().push(message);
+        }
+    }
+
+    // whether a '(', '{' or '[' is still open when the tokens run out
+    fn has_unclosed_delimiter(&self) -> bool {
+        let depth =
+            self.tokens
+                .borrow()
+                .iter()
+                .fold(0isize, |depth, token| match token.token_type() {
+                    TokenType::SingleCharacters(
+                        SingleCharacter::LeftParen
+                        | SingleCharacter::LeftBrace
+                        | SingleCharacter::LeftBracket,
+                    ) => depth + 1,
+                    TokenType::SingleCharacters(
+                        SingleCharacter::RightParen
+                        | SingleCharacter::RightBrace
+                        | SingleCharacter::RightBracket,
+                    ) => depth - 1,
+                    _ => depth,
+                });
+
+        depth > 0
+    }
+
+    fn tokens_from(&self, start: usize) -> Vec<Token> {
+        self.tokens.borrow()[start..*self.current.borrow()].to_vec()
+    }
+
+    fn error(&self, token: &Token, message: &str) -> Error {
+        Error(ErrorType::Parse(
+            self.build_parser_error_msg(token, message),
+        ))
+    }
+
+    fn build_parser_error_msg(&self, token: &Token, message: &str) -> String {
         match token.token_type() {
             TokenType::Keywords(Keyword::Eof) => format!("{} at end {}", token.line(), message),
             _ => format!("{} at {} {}", token.line(), token.lexeme(), message),
         }
     }
 
-    fn synchronize(&self) -> Result<()> {
-        self.advance()?;
-
+    // skips to the start of the next statement. Inside a block it stops short of a `}`, so the
+    // block still closes where it should
+    fn synchronize(&self) {
         while !self.is_end() {
+            if *self.block_depth.borrow() > 0
+                && self.check(TokenType::SingleCharacters(SingleCharacter::RightBrace))
+            {
+                return;
+            }
+
+            self.advance();
+
             if self.previous().token_type()
                 == TokenType::SingleCharacters(SingleCharacter::Semicolon)
             {
-                return Ok(());
+                return;
             }
 
-            if let TokenType::Keywords(_) = self.peek().token_type() {
-                return Ok(());
+            if let TokenType::Keywords(keyword) = self.peek().token_type() {
+                if STATEMENT_START.contains(&keyword) {
+                    return;
+                }
             };
-
-            self.advance()?;
         }
+    }
+}
+
+// tokens that close a construct or end a statement, which an expression can't start with
+fn is_closing(token: &Token) -> bool {
+    matches!(
+        token.token_type(),
+        TokenType::SingleCharacters(
+            SingleCharacter::RightParen
+                | SingleCharacter::RightBrace
+                | SingleCharacter::RightBracket
+                | SingleCharacter::Semicolon
+                | SingleCharacter::Comma
+                | SingleCharacter::Colon
+        )
+    )
+}
 
-        Ok(())
+fn starts_statement(token: &Token) -> bool {
+    match token.token_type() {
+        TokenType::Keywords(keyword) => STATEMENT_START.contains(&keyword),
+        _ => false,
     }
 }
@@ -0,0 +1,421 @@
+use crate::errors::{Error, ErrorType};
+use crate::expr::Expr;
+use crate::scanner::token::Token;
+use crate::scanner::token_type::TokenType;
+use crate::stmt::{Class, Stmt};
+use crate::Result;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+/// Runs between parsing and execution. It binds every local variable use to the scope that
+/// declares it, recording the distance on the `Variable` and `Assign` nodes, and reports the
+/// mistakes that can be caught without running anything.
+pub struct Resolver {
+    // the enclosing local scopes, innermost last. Globals aren't tracked
+    scopes: RefCell<Vec<HashMap<String, Local>>>,
+    function: Cell<FunctionKind>,
+    class: Cell<ClassKind>,
+    errors: RefCell<Vec<String>>,
+    warnings: RefCell<Vec<(usize, String)>>,
+}
+
+struct Local {
+    name: Token,
+    // false between the declaration and the end of the initializer
+    defined: bool,
+    used: bool,
+    // parameters and functions aren't reported when unused, only `var`s
+    warn_unused: bool,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum FunctionKind {
+    None,
+    Function,
+    Method,
+    Initializer,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ClassKind {
+    None,
+    Class,
+    Subclass,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Self {
+            scopes: RefCell::new(vec![]),
+            function: Cell::new(FunctionKind::None),
+            class: Cell::new(ClassKind::None),
+            errors: RefCell::new(vec![]),
+            warnings: RefCell::new(vec![]),
+        }
+    }
+
+    /// Resolves a whole program. Returns the warnings, or every error found if there were any.
+    pub fn resolve(&self, statements: &[Stmt]) -> Result<Vec<String>> {
+        self.resolve_statements(statements);
+
+        let errors = self.errors.take();
+        if !errors.is_empty() {
+            return Err(Error(ErrorType::Resolve(errors.join("\n"))));
+        }
+
+        let mut warnings = self.warnings.take();
+        warnings.sort_by_key(|(line, _)| *line);
+
+        Ok(warnings.into_iter().map(|(_, warning)| warning).collect())
+    }
+
+    fn resolve_statements(&self, statements: &[Stmt]) {
+        for statement in statements {
+            self.resolve_statement(statement);
+        }
+    }
+
+    fn resolve_statement(&self, statement: &Stmt) {
+        match statement {
+            Stmt::Block(block) => {
+                self.begin_scope();
+                self.resolve_statements(&block.statements);
+                self.end_scope();
+            }
+            Stmt::Class(class) => self.resolve_class(class),
+            Stmt::Expression(expression) => self.resolve_expr(&expression.expression),
+            Stmt::Function(function) => {
+                self.declare(&function.name, false);
+                self.define(&function.name);
+
+                self.resolve_function(&function.params, &function.body, FunctionKind::Function);
+            }
+            Stmt::If(if_stmt) => {
+                self.resolve_expr(&if_stmt.condition);
+                self.resolve_statement(&if_stmt.then_branch);
+
+                if let Some(else_branch) = &if_stmt.else_branch {
+                    self.resolve_statement(else_branch);
+                }
+            }
+            Stmt::Import(import) => {
+                self.declare(&import.name, false);
+                self.define(&import.name);
+            }
+            Stmt::Print(print) => self.resolve_expr(&print.expression),
+            Stmt::Return(return_stmt) => {
+                if self.function.get() == FunctionKind::None {
+                    self.error(&return_stmt.keyword, "Can't return from top-level code.");
+                }
+
+                if let Some(value) = &return_stmt.value {
+                    if self.function.get() == FunctionKind::Initializer {
+                        self.error(
+                            &return_stmt.keyword,
+                            "Can't return a value from an initializer.",
+                        );
+                    }
+
+                    self.resolve_expr(value);
+                }
+            }
+            Stmt::Throw(throw) => self.resolve_expr(&throw.value),
+            Stmt::Try(try_stmt) => {
+                self.begin_scope();
+                self.resolve_statements(&try_stmt.body);
+                self.end_scope();
+
+                // the error variable shares a scope with the catch body, like parameters do
+                if let Some(catch) = &try_stmt.catch {
+                    self.begin_scope();
+                    self.declare(&catch.name, false);
+                    self.define(&catch.name);
+                    self.resolve_statements(&catch.body);
+                    self.end_scope();
+                }
+
+                if let Some(finally) = &try_stmt.finally {
+                    self.begin_scope();
+                    self.resolve_statements(finally);
+                    self.end_scope();
+                }
+            }
+            Stmt::Var(var) => {
+                self.declare(&var.name, true);
+
+                if let Some(initializer) = &var.initializer {
+                    self.resolve_expr(initializer);
+                }
+
+                self.define(&var.name);
+            }
+            Stmt::While(while_stmt) => {
+                self.resolve_expr(&while_stmt.condition);
+                self.resolve_statement(&while_stmt.body);
+
+                if let Some(increment) = &while_stmt.increment {
+                    self.resolve_expr(increment);
+                }
+            }
+            Stmt::Break(..) | Stmt::Continue(..) | Stmt::Error(..) => {}
+        }
+    }
+
+    fn resolve_expr(&self, expr: &Expr) {
+        match expr {
+            Expr::Assign(assign) => {
+                self.resolve_expr(&assign.value);
+                self.bind(&assign.name, &assign.depth, false);
+            }
+            Expr::Binary(binary) => {
+                self.resolve_expr(&binary.left);
+                self.resolve_expr(&binary.right);
+            }
+            Expr::Call(call) => {
+                self.resolve_expr(&call.callee);
+                call.arguments
+                    .iter()
+                    .for_each(|argument| self.resolve_expr(argument));
+            }
+            Expr::Comma(comma) => {
+                self.resolve_expr(&comma.left);
+                self.resolve_expr(&comma.right);
+            }
+            Expr::Conditional(conditional) => {
+                self.resolve_expr(&conditional.condition);
+                self.resolve_expr(&conditional.then_branch);
+                self.resolve_expr(&conditional.else_branch);
+            }
+            Expr::Function(function) => {
+                self.resolve_function(&function.params, &function.body, FunctionKind::Function)
+            }
+            Expr::Get(get) => self.resolve_expr(&get.object),
+            Expr::Grouping(grouping) => self.resolve_expr(&grouping.expression),
+            Expr::Index(index) => {
+                self.resolve_expr(&index.object);
+                self.resolve_expr(&index.index);
+            }
+            Expr::List(list) => list
+                .elements
+                .iter()
+                .for_each(|element| self.resolve_expr(element)),
+            Expr::Logical(logical) => {
+                self.resolve_expr(&logical.left);
+                self.resolve_expr(&logical.right);
+            }
+            Expr::Map(map) => map.entries.iter().for_each(|(key, value)| {
+                self.resolve_expr(key);
+                self.resolve_expr(value);
+            }),
+            Expr::Set(set) => {
+                self.resolve_expr(&set.value);
+                self.resolve_expr(&set.object);
+            }
+            Expr::SetIndex(set_index) => {
+                self.resolve_expr(&set_index.object);
+                self.resolve_expr(&set_index.index);
+                self.resolve_expr(&set_index.value);
+            }
+            Expr::Unary(unary) => self.resolve_expr(&unary.right),
+            Expr::Variable(variable) => {
+                let in_own_initializer = self
+                    .scopes
+                    .borrow()
+                    .last()
+                    .and_then(|scope| scope.get(variable.name.lexeme()))
+                    .is_some_and(|local| !local.defined);
+
+                if in_own_initializer {
+                    self.error(
+                        &variable.name,
+                        "Can't read local variable in its own initializer.",
+                    );
+                }
+
+                self.resolve_local(&variable.name, &variable.depth);
+            }
+            Expr::Super(super_expr) => {
+                match self.class.get() {
+                    ClassKind::None => {
+                        self.error(&super_expr.keyword, "Can't use 'super' outside of a class.")
+                    }
+                    ClassKind::Class => self.error(
+                        &super_expr.keyword,
+                        "Can't use 'super' in a class with no superclass.",
+                    ),
+                    ClassKind::Subclass => {}
+                }
+
+                self.resolve_local(&super_expr.keyword, &super_expr.depth);
+            }
+            Expr::This(this) => {
+                if self.class.get() == ClassKind::None {
+                    self.error(&this.keyword, "Can't use 'this' outside of a class.");
+                }
+
+                self.resolve_local(&this.keyword, &this.depth);
+            }
+            Expr::Error(..) | Expr::Literal(..) => {}
+        }
+    }
+
+    // methods are resolved inside a scope binding `this`, itself inside one binding `super` when
+    // there's a superclass, matching the scopes the interpreter creates
+    fn resolve_class(&self, class: &Class) {
+        let enclosing = self.class.replace(ClassKind::Class);
+
+        self.declare(&class.name, false);
+        self.define(&class.name);
+
+        if let Some(superclass) = &class.superclass {
+            if superclass.name.lexeme() == class.name.lexeme() {
+                self.error(&superclass.name, "A class can't inherit from itself.");
+            }
+
+            self.class.set(ClassKind::Subclass);
+            self.resolve_local(&superclass.name, &superclass.depth);
+
+            self.begin_scope();
+            self.bind_implicit("super");
+        }
+
+        self.begin_scope();
+        self.bind_implicit("this");
+
+        for method in &class.methods {
+            let kind = match method.name.lexeme() {
+                "init" => FunctionKind::Initializer,
+                _ => FunctionKind::Method,
+            };
+
+            self.resolve_function(&method.params, &method.body, kind);
+        }
+
+        self.end_scope();
+
+        if class.superclass.is_some() {
+            self.end_scope();
+        }
+
+        self.class.set(enclosing);
+    }
+
+    fn resolve_function(&self, params: &[Token], body: &[Stmt], kind: FunctionKind) {
+        let enclosing = self.function.replace(kind);
+
+        self.begin_scope();
+        for param in params {
+            self.declare(param, false);
+            self.define(param);
+        }
+        self.resolve_statements(body);
+        self.end_scope();
+
+        self.function.set(enclosing);
+    }
+
+    // a read of `name`, which counts as a use
+    fn resolve_local(&self, name: &Token, depth: &Cell<Option<usize>>) {
+        self.bind(name, depth, true);
+    }
+
+    // records how many scopes out `name` is declared. Names not found in any local scope are
+    // left as globals
+    fn bind(&self, name: &Token, depth: &Cell<Option<usize>>, is_use: bool) {
+        let mut scopes = self.scopes.borrow_mut();
+
+        for (distance, scope) in scopes.iter_mut().rev().enumerate() {
+            if let Some(local) = scope.get_mut(name.lexeme()) {
+                local.used |= is_use;
+                depth.set(Some(distance));
+
+                return;
+            }
+        }
+    }
+
+    fn begin_scope(&self) {
+        self.scopes.borrow_mut().push(HashMap::new());
+    }
+
+    fn end_scope(&self) {
+        let scope = self.scopes.borrow_mut().pop().unwrap_or_default();
+
+        for local in scope.values() {
+            if local.warn_unused && !local.used {
+                self.warnings.borrow_mut().push((
+                    local.name.line(),
+                    build_msg(
+                        &local.name,
+                        &format!("Local variable '{}' is never used.", local.name.lexeme()),
+                    ),
+                ));
+            }
+        }
+    }
+
+    fn declare(&self, name: &Token, warn_unused: bool) {
+        let mut scopes = self.scopes.borrow_mut();
+        let scope = match scopes.last_mut() {
+            Some(scope) => scope,
+            None => return,
+        };
+
+        if scope.contains_key(name.lexeme()) {
+            drop(scopes);
+            self.error(name, "Already a variable with this name in this scope.");
+
+            return;
+        }
+
+        scope.insert(
+            name.lexeme().to_string(),
+            Local {
+                name: name.clone(),
+                defined: false,
+                used: false,
+                warn_unused,
+            },
+        );
+    }
+
+    // a name the interpreter binds itself, which is never reported as unused
+    fn bind_implicit(&self, name: &str) {
+        if let Some(scope) = self.scopes.borrow_mut().last_mut() {
+            scope.insert(
+                name.to_string(),
+                Local {
+                    name: Token::new(TokenType::Identifier, name, None, 0),
+                    defined: true,
+                    used: true,
+                    warn_unused: false,
+                },
+            );
+        }
+    }
+
+    fn define(&self, name: &Token) {
+        if let Some(local) = self
+            .scopes
+            .borrow_mut()
+            .last_mut()
+            .and_then(|scope| scope.get_mut(name.lexeme()))
+        {
+            local.defined = true;
+        }
+    }
+
+    fn error(&self, token: &Token, message: &str) {
+        self.errors.borrow_mut().push(build_msg(token, message));
+    }
+}
+
+fn build_msg(token: &Token, message: &str) -> String {
+    format!("{} at {} {}", token.line(), token.lexeme(), message)
+}
@@ -1,7 +1,7 @@
 use crate::errors::Error;
-pub use std::str;
 
 pub mod pretty_printer;
+#[allow(clippy::module_inception)]
 pub mod scanner;
 pub mod token;
 pub mod token_type;
@@ -13,5 +13,6 @@ const CARRIAGE_RETURN: u8 = b'\r';
 const TAB: u8 = b'\t';
 const QUOTE: u8 = b'"';
 const PERIOD: u8 = b'.';
+const EQUAL: u8 = b'=';
 
 type Result<T> = std::result::Result<T, Error>;
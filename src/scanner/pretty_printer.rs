@@ -3,18 +3,22 @@ use crate::scanner::token_type::Literal;
 
 pub struct PrettyPrinter {
     inner: String,
-    indent_size: usize,
+}
+
+impl Default for PrettyPrinter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl PrettyPrinter {
     pub fn new() -> Self {
         Self {
             inner: String::new(),
-            indent_size: 4,
         }
     }
 
-    pub fn print_expr(mut self, expr: &Expr<'_>) {
+    pub fn print_expr(mut self, expr: &Expr) {
         self.push_expr(expr);
 
         println!("{}", self.inner);
@@ -44,7 +48,7 @@ impl PrettyPrinter {
     fn push_expr(&mut self, expr: &Expr) -> &mut Self {
         match expr {
             Expr::Assign(assign) => self
-                .push(assign.name.build_string())
+                .push(assign.name.lexeme())
                 .push(" = ")
                 .push_expr(assign.value.as_ref()),
 
@@ -56,25 +60,77 @@ impl PrettyPrinter {
                 .push_expr(&bin.right),
 
             Expr::Call(call) => {
-                self.push_expr(call.callee.as_ref()).push_char(')');
+                self.push_expr(call.callee.as_ref()).push_char('(');
 
                 for arg in &call.arguments {
-                    self.push_expr(arg).push_char(',');
+                    self.push_expr(arg).push(", ");
+                }
+
+                if !call.arguments.is_empty() {
+                    self.pop().pop();
+                }
+
+                self.push_char(')')
+            }
+
+            Expr::Comma(comma) => self
+                .push_expr(&comma.left)
+                .push(", ")
+                .push_expr(&comma.right),
+
+            Expr::Conditional(conditional) => self
+                .push_expr(&conditional.condition)
+                .push(" ? ")
+                .push_expr(&conditional.then_branch)
+                .push(" : ")
+                .push_expr(&conditional.else_branch),
+
+            Expr::Error(..) => self.push("<error>"),
+
+            Expr::Function(function) => {
+                self.push("fun (");
+
+                for param in &function.params {
+                    self.push(param.lexeme()).push(", ");
                 }
 
-                self.pop()
+                if !function.params.is_empty() {
+                    self.pop().pop();
+                }
+
+                self.push(") { ... }")
             }
 
             Expr::Get(get) => self
                 .push_expr(get.object.as_ref())
                 .push_char('.')
-                .push(get.name.build_string()),
+                .push(get.name.lexeme()),
 
             Expr::Grouping(grouping) => self
                 .push_char('(')
                 .push_expr(grouping.expression.as_ref())
                 .push_char(')'),
 
+            Expr::Index(index) => self
+                .push_expr(&index.object)
+                .push_char('[')
+                .push_expr(&index.index)
+                .push_char(']'),
+
+            Expr::List(list) => {
+                self.push_char('[');
+
+                for element in &list.elements {
+                    self.push_expr(element).push(", ");
+                }
+
+                if !list.elements.is_empty() {
+                    self.pop().pop();
+                }
+
+                self.push_char(']')
+            }
+
             Expr::Literal(literal) => match &literal.value {
                 Literal::String(..) => self
                     .push_char('"')
@@ -83,18 +139,43 @@ impl PrettyPrinter {
                 _ => self.push(literal.value.build_string()),
             },
 
+            Expr::Logical(logical) => self
+                .push_expr(&logical.left)
+                .push_char(' ')
+                .push(logical.operator.lexeme())
+                .push_char(' ')
+                .push_expr(&logical.right),
+
+            Expr::Map(map) => {
+                self.push_char('{');
+
+                for (key, value) in &map.entries {
+                    self.push_expr(key).push(": ").push_expr(value).push(", ");
+                }
+
+                if !map.entries.is_empty() {
+                    self.pop().pop();
+                }
+
+                self.push_char('}')
+            }
+
             Expr::Set(set) => self
                 .push_expr(set.object.as_ref())
                 .push_char('.')
-                .push(set.name.build_string())
+                .push(set.name.lexeme())
                 .push(" = ")
                 .push_expr(set.value.as_ref())
                 .push_char(';'),
 
-            Expr::Super(s) => self
-                .push("super")
-                .push_char('.')
-                .push(s.method.build_string()),
+            Expr::SetIndex(set) => self
+                .push_expr(&set.object)
+                .push_char('[')
+                .push_expr(&set.index)
+                .push("] = ")
+                .push_expr(&set.value),
+
+            Expr::Super(s) => self.push("super").push_char('.').push(s.method.lexeme()),
 
             Expr::This(..) => self.push("this"),
 
@@ -102,7 +183,7 @@ impl PrettyPrinter {
                 .push(unary.operator.build_string())
                 .push_expr(unary.right.as_ref()),
 
-            Expr::Variable(var) => self.push(var.name.build_string()),
+            Expr::Variable(var) => self.push(var.name.lexeme()),
         }
     }
 }
@@ -1,15 +1,36 @@
 use crate::errors::Error;
-use crate::errors::ErrorType::{Parse, Scanner as ScannerError};
+use crate::errors::ErrorType::Incomplete;
+use crate::errors::ErrorType::Scanner as ScannerError;
 use crate::scanner;
 use crate::scanner::token::Token;
 use crate::scanner::token_type::{Keyword, Literal, NumberType, TokenType};
-use crate::scanner::{CARRIAGE_RETURN, NEW_LINE, PERIOD, QUOTE, SLASH, TAB, WHITE_SPACE};
+use crate::scanner::{CARRIAGE_RETURN, EQUAL, NEW_LINE, PERIOD, QUOTE, SLASH, TAB, WHITE_SPACE};
 use std::str;
 use std::str::FromStr;
 
+/// Reads `text` as a number literal: digits, with a fractional part after a `.` for a float. Whole
+/// numbers too big for an integer are floats. Anything else, such as an exponent or `inf`, isn't a
+/// number.
+pub fn parse_number(text: &str) -> Option<NumberType> {
+    let digits = |part: &str| !part.is_empty() && part.bytes().all(|byte| byte.is_ascii_digit());
+
+    let valid = match text.split_once('.') {
+        Some((whole, fraction)) => digits(whole) && digits(fraction),
+        None => digits(text),
+    };
+    if !valid {
+        return None;
+    }
+
+    text.parse::<i64>()
+        .map(NumberType::Integer)
+        .or_else(|_| text.parse::<f64>().map(NumberType::Float))
+        .ok()
+}
+
 pub struct Scanner<'scanner> {
     source: &'scanner [u8],
-    tokens: Vec<Token<'scanner>>,
+    tokens: Vec<Token>,
     start: usize,
     current: usize,
     line: usize,
@@ -22,14 +43,38 @@ impl<'scanner> Scanner<'scanner> {
             tokens: vec![],
             start: 0,
             current: 0,
-            line: 0,
+            line: 1,
+        }
+    }
+
+    pub fn scan_tokens(&mut self) -> scanner::Result<&Vec<Token>> {
+        if let Some(err) = self.scan_all().into_iter().next() {
+            return Err(err);
         }
+
+        Ok(&self.tokens)
     }
 
-    pub fn scan_tokens(&mut self) -> &Vec<Token> {
-        //TODO: theres gotta be a better way....
-        while self.scan_token().is_some() {
+    /// Scans all of the source, for editor tooling. Input that isn't a token becomes a
+    /// `TokenType::Error` token, which the parser turns into an `Expr::Error`, and the error
+    /// messages are returned alongside the tokens.
+    pub fn scan_tokens_tolerant(&mut self) -> (&Vec<Token>, Vec<String>) {
+        let errors = self.scan_all().iter().map(error_message).collect();
+
+        (&self.tokens, errors)
+    }
+
+    // scanning carries on past errors, so every one of them is found
+    fn scan_all(&mut self) -> Vec<Error> {
+        let mut errors = vec![];
+
+        while !self.is_end() {
             self.start = self.current;
+
+            if let Err(err) = self.scan_token() {
+                self.add_error_token(&err);
+                errors.push(err);
+            }
         }
 
         self.tokens.push(Token::new(
@@ -39,78 +84,69 @@ impl<'scanner> Scanner<'scanner> {
             self.line,
         ));
 
-        &self.tokens
+        errors
     }
 
     fn is_end(&self) -> bool {
         self.source.len() <= self.current
     }
 
-    fn scan_token(&mut self) -> Option<()> {
-        if self.is_end() {
-            return None;
-        }
+    fn scan_token(&mut self) -> scanner::Result<()> {
+        let character = self.advance();
 
-        let token_as_u8 = vec![self.advance()];
+        if is_skip(character) {
+            if character == NEW_LINE {
+                self.line += 1;
+            }
 
-        // TODO: will this work as expected? ... Make sure to write a test
-        if is_skip(token_as_u8[0]) {
-            return Some(());
+            return Ok(());
         }
 
-        let token_as_str: &str = str::from_utf8(&token_as_u8).unwrap(); //todo: fix
+        if let Ok(token) = TokenType::from_str(&char::from(character).to_string()) {
+            if token.is_slash() && self.match_token(SLASH) {
+                // grab all the comments till End of Line
+                while self.peek() != NEW_LINE && !self.is_end() {
+                    self.advance();
+                }
+
+                return Ok(());
+            }
+
+            if let TokenType::SingleOrDoubles(..) = token {
+                if self.match_token(EQUAL) {
+                    // safe cause every single or double has an `=` suffixed form
+                    let double = TokenType::from_str(self.get_text()).unwrap();
+                    self.add_token(double, None);
 
-        if let Ok(token) = TokenType::from_str(token_as_str) {
-            if token.is_slash() {
-                if self.match_token(SLASH) {
-                    // grab all the comments till End of Line
-                    while self.peek() != NEW_LINE && !self.is_end() {
-                        self.advance();
-                    }
-                    return Some(());
+                    return Ok(());
                 }
             }
 
             self.add_token(token, None);
 
-            return Some(());
+            return Ok(());
         }
 
-        // // TODO: is this safe to do?
-        // let token_as_u8 = [token_as_u8[0], self.advance()];
-        // let token_as_str: &str = str::from_utf8(&token_as_u8).unwrap(); //todo: fix
-        //
-        // if let Ok(token) = TokenType::from_str(token_as_str) {
-        //     self.add_token(token, None);
-        //
-        //     return Some(());
-        // }
-
-        if token_as_u8[0] == QUOTE {
-            return match self.handle_string_literal() {
-                Ok(..) => Some(()),
-                Err(..) => None,
-            };
+        if character == QUOTE {
+            return self.handle_string_literal();
         }
 
-        if token_as_u8[0].is_ascii_digit() {
-            return match self.handle_number_literal() {
-                Ok(..) => Some(()),
-                Err(..) => None,
-            };
+        if character.is_ascii_digit() {
+            return self.handle_number_literal();
         }
 
-        if token_as_u8[0].is_ascii_alphabetic() {
-            match self.handle_identifier_literal() {
-                Ok(..) => Some(()),
-                Err(..) => None,
-            };
+        if is_identifier_start(character) {
+            return self.handle_identifier_literal();
         }
 
-        None
+        Err(Error::from(ScannerError(format!(
+            "{} Unexpected character '{}'.",
+            self.line,
+            char::from(character)
+        ))))
     }
 
-    fn handle_string_literal(&mut self) -> scanner::Result<Option<()>> {
+    fn handle_string_literal(&mut self) -> scanner::Result<()> {
         while self.peek() != QUOTE && !self.is_end() {
             if self.peek() == NEW_LINE {
                 self.line += 1;
@@ -120,27 +156,38 @@ impl<'scanner> Scanner<'scanner> {
         }
 
         if self.is_end() {
-            return Err(Error::from(ScannerError(String::from(
-                "error attempting to parse string literal",
-            ))));
+            return Err(Error::from(Incomplete(Box::new(Error::from(
+                ScannerError(format!("{} Unterminated string.", self.line)),
+            )))));
         }
 
         self.advance();
 
-        let result = str::from_utf8(&self.source[self.start + 1..self.current - 1]).unwrap();
+        let result = String::from_utf8_lossy(&self.source[self.start + 1..self.current - 1]);
         self.add_token(
             TokenType::Keywords(Keyword::String),
-            Some(Literal::String(result)),
+            Some(Literal::String(result.into_owned())),
         );
 
-        Ok(Some(()))
+        Ok(())
     }
 
-    fn add_token(&mut self, token: TokenType, literal: Option<Literal<'scanner>>) {
+    fn add_token(&mut self, token: TokenType, literal: Option<Literal>) {
         let token = self.build_token(token, literal);
         self.tokens.push(token);
     }
 
+    // the text that failed to scan, which may not be valid utf-8 when it's a stray byte
+    fn add_error_token(&mut self, err: &Error) {
+        let text = String::from_utf8_lossy(&self.source[self.start..self.current]);
+        self.tokens.push(Token::new(
+            TokenType::Error,
+            text,
+            Some(Literal::String(error_message(err))),
+            self.line,
+        ));
+    }
+
     fn advance(&mut self) -> u8 {
         let token = self.source[self.current];
         self.current += 1;
@@ -168,7 +215,7 @@ impl<'scanner> Scanner<'scanner> {
         true
     }
 
-    fn build_token(&self, token: TokenType, literal: Option<Literal<'scanner>>) -> Token<'scanner> {
+    fn build_token(&self, token: TokenType, literal: Option<Literal>) -> Token {
         let text = self.get_text();
         Token::new(token, text, literal, self.line)
     }
@@ -177,7 +224,7 @@ impl<'scanner> Scanner<'scanner> {
         str::from_utf8(&self.source[self.start..self.current]).unwrap()
     }
 
-    fn handle_number_literal(&mut self) -> scanner::Result<Option<()>> {
+    fn handle_number_literal(&mut self) -> scanner::Result<()> {
         while self.peek().is_ascii_digit() {
             self.advance();
         }
@@ -190,26 +237,26 @@ impl<'scanner> Scanner<'scanner> {
             }
         }
 
-        let result = str::from_utf8(&self.source[self.start..self.current]).unwrap();
+        let result = self.get_text();
 
-        match result.parse::<i64>() {
-            Ok(number) => self.add_token(
+        match parse_number(result) {
+            Some(number @ NumberType::Integer(..)) => self.add_token(
                 TokenType::Keywords(Keyword::Integer),
-                Some(Literal::Number(NumberType::Integer(number))),
+                Some(Literal::Number(number)),
             ),
-            Err(..) => match result.parse::<f64>() {
-                Ok(float) => self.add_token(
-                    TokenType::Keywords(Keyword::Float),
-                    Some(Literal::Number(NumberType::Float(float))),
-                ),
-                Err(_) => {
-                    println!("{}", result);
-                    unimplemented!()
-                }
-            },
+            Some(number @ NumberType::Float(..)) => self.add_token(
+                TokenType::Keywords(Keyword::Float),
+                Some(Literal::Number(number)),
+            ),
+            None => {
+                return Err(Error::from(ScannerError(format!(
+                    "{} Invalid number literal '{}'.",
+                    self.line, result
+                ))))
+            }
         }
 
-        Ok(Some(()))
+        Ok(())
     }
 
     fn peek_next(&self) -> u8 {
@@ -219,8 +266,8 @@ impl<'scanner> Scanner<'scanner> {
         }
     }
 
-    fn handle_identifier_literal(&mut self) -> scanner::Result<Option<()>> {
-        while self.peek().is_ascii_alphanumeric() {
+    fn handle_identifier_literal(&mut self) -> scanner::Result<()> {
+        while is_identifier_start(self.peek()) || self.peek().is_ascii_digit() {
             self.advance();
         }
 
@@ -229,13 +276,29 @@ impl<'scanner> Scanner<'scanner> {
         match Keyword::from_str(word) {
             Ok(keyword) => self.add_token(TokenType::Keywords(keyword), None),
             // TODO: this might not be the best way to go about it should identifiers really just be wrapped string literals?
-            Err(..) => self.add_token(TokenType::Identifier, Some(Literal::String(word))),
+            Err(..) => self.add_token(
+                TokenType::Identifier,
+                Some(Literal::String(word.to_string())),
+            ),
         }
 
-        Ok(Some(()))
+        Ok(())
     }
 }
 
+// a scanner error's message, without the description of its kind
+fn error_message(Error(err): &Error) -> String {
+    match err {
+        ScannerError(message) => message.clone(),
+        Incomplete(err) => error_message(err),
+        err => err.to_string(),
+    }
+}
+
+fn is_identifier_start(character: u8) -> bool {
+    character.is_ascii_alphabetic() || character == b'_'
+}
+
 fn is_skip(character: u8) -> bool {
     character == CARRIAGE_RETURN
         || character == WHITE_SPACE
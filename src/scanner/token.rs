@@ -1,30 +1,29 @@
 use crate::scanner::token_type::{Literal, TokenType};
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
-#[derive(Copy, Clone, Debug, PartialEq)]
-pub struct Token<'token> {
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Token {
     token_type: TokenType,
-    lexeme: &'token str,
-    literal: Option<Literal<'token>>,
+    lexeme: String,
+    literal: Option<Literal>,
     line: usize,
 }
 
-impl<'token> Display for Token<'token> {
+impl Display for Token {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{} {} {}", self.token_type, self.lexeme, self.line)
     }
 }
 
-impl<'token> Token<'token> {
-    pub fn new(
-        token_type: TokenType,
-        lexeme: &'token str,
-        literal: Option<Literal<'token>>,
-        line: usize,
-    ) -> Self {
+impl Token {
+    pub fn new<S>(token_type: TokenType, lexeme: S, literal: Option<Literal>, line: usize) -> Self
+    where
+        S: Into<String>,
+    {
         Self {
             token_type,
-            lexeme,
+            lexeme: lexeme.into(),
             literal,
             line,
         }
@@ -39,8 +38,8 @@ impl<'token> Token<'token> {
         self.token_type.build_string()
     }
 
-    pub fn literal(&self) -> Option<Literal<'token>> {
-        self.literal
+    pub fn literal(&self) -> Option<Literal> {
+        self.literal.clone()
     }
 
     pub fn line(&self) -> usize {
@@ -51,7 +50,7 @@ impl<'token> Token<'token> {
         self.token_type
     }
 
-    pub fn lexeme(&self) -> &'token str {
-        self.lexeme
+    pub fn lexeme(&self) -> &str {
+        &self.lexeme
     }
 }
@@ -1,13 +1,16 @@
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::str;
 use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum TokenType {
     SingleCharacters(SingleCharacter),
     SingleOrDoubles(SingleOrDouble),
     Keywords(Keyword),
     Identifier,
+    /// Input the scanner couldn't make a token of. Its literal is the scanner's error message.
+    Error,
 }
 
 impl TokenType {
@@ -17,6 +20,7 @@ impl TokenType {
             TokenType::SingleOrDoubles(token) => token.build_string(),
             TokenType::Keywords(token) => token.build_string(),
             TokenType::Identifier => "identifier".to_string(),
+            TokenType::Error => "error".to_string(),
         }
     }
 }
@@ -34,6 +38,7 @@ impl Display for TokenType {
             TokenType::SingleOrDoubles(token) => write!(f, "{}", token),
             TokenType::Keywords(token) => write!(f, "{}", token),
             TokenType::Identifier => write!(f, "identifier"),
+            TokenType::Error => write!(f, "error"),
         }
     }
 }
@@ -58,12 +63,14 @@ impl FromStr for TokenType {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum SingleCharacter {
     LeftParen,
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
 
     Comma,
     Dot,
@@ -72,6 +79,8 @@ pub enum SingleCharacter {
     Semicolon,
     Slash,
     Star,
+    Question,
+    Colon,
 }
 
 impl SingleCharacter {
@@ -81,6 +90,8 @@ impl SingleCharacter {
             SingleCharacter::RightParen => ")".to_string(),
             SingleCharacter::LeftBrace => "{".to_string(),
             SingleCharacter::RightBrace => "}".to_string(),
+            SingleCharacter::LeftBracket => "[".to_string(),
+            SingleCharacter::RightBracket => "]".to_string(),
             SingleCharacter::Comma => ",".to_string(),
             SingleCharacter::Dot => ".".to_string(),
             SingleCharacter::Minus => "-".to_string(),
@@ -88,6 +99,8 @@ impl SingleCharacter {
             SingleCharacter::Semicolon => ";".to_string(),
             SingleCharacter::Slash => "/".to_string(),
             SingleCharacter::Star => "*".to_string(),
+            SingleCharacter::Question => "?".to_string(),
+            SingleCharacter::Colon => ":".to_string(),
         }
     }
 }
@@ -101,6 +114,8 @@ impl FromStr for SingleCharacter {
             ")" => Ok(SingleCharacter::RightParen),
             "{" => Ok(SingleCharacter::LeftBrace),
             "}" => Ok(SingleCharacter::RightBrace),
+            "[" => Ok(SingleCharacter::LeftBracket),
+            "]" => Ok(SingleCharacter::RightBracket),
             "," => Ok(SingleCharacter::Comma),
             "." => Ok(SingleCharacter::Dot),
             "-" => Ok(SingleCharacter::Minus),
@@ -108,6 +123,8 @@ impl FromStr for SingleCharacter {
             ";" => Ok(SingleCharacter::Semicolon),
             "/" => Ok(SingleCharacter::Slash),
             "*" => Ok(SingleCharacter::Star),
+            "?" => Ok(SingleCharacter::Question),
+            ":" => Ok(SingleCharacter::Colon),
             _ => Err(()),
         }
     }
@@ -120,6 +137,8 @@ impl Display for SingleCharacter {
             SingleCharacter::RightParen => write!(f, ")"),
             SingleCharacter::LeftBrace => write!(f, r#"{{"#),
             SingleCharacter::RightBrace => write!(f, r#"}}"#),
+            SingleCharacter::LeftBracket => write!(f, "["),
+            SingleCharacter::RightBracket => write!(f, "]"),
             SingleCharacter::Comma => write!(f, ","),
             SingleCharacter::Dot => write!(f, "."),
             SingleCharacter::Minus => write!(f, "-"),
@@ -127,11 +146,13 @@ impl Display for SingleCharacter {
             SingleCharacter::Semicolon => write!(f, ";"),
             SingleCharacter::Slash => write!(f, "/"),
             SingleCharacter::Star => write!(f, "*"),
+            SingleCharacter::Question => write!(f, "?"),
+            SingleCharacter::Colon => write!(f, ":"),
         }
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum SingleOrDouble {
     Bang,
     BangEqual,
@@ -151,7 +172,7 @@ impl SingleOrDouble {
             SingleOrDouble::Equal => "=".to_string(),
             SingleOrDouble::EqualEqual => "==".to_string(),
             SingleOrDouble::Greater => ">".to_string(),
-            SingleOrDouble::GreaterEqual => "=>".to_string(),
+            SingleOrDouble::GreaterEqual => ">=".to_string(),
             SingleOrDouble::Less => "<".to_string(),
             SingleOrDouble::LessEqual => "<=".to_string(),
         }
@@ -191,15 +212,15 @@ impl Display for SingleOrDouble {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub enum Literal<'literal> {
-    String(&'literal str),
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Literal {
+    String(String),
     Number(NumberType),
     Bool(bool),
     Nil,
 }
 
-impl<'literal> Literal<'literal> {
+impl Literal {
     pub fn build_string(&self) -> String {
         match self {
             Literal::String(str) => str.to_string(),
@@ -210,15 +231,23 @@ impl<'literal> Literal<'literal> {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum NumberType {
     Integer(i64),
     Float(f64),
 }
 
+// floats keep a fractional part, `2.0` rather than `2`, so they never read as integers. They're
+// written out in full, never with an exponent, and the values that aren't numbers are `nan`,
+// `inf` and `-inf`
 impl Display for NumberType {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
+            NumberType::Float(float) if float.is_nan() => write!(f, "nan"),
+            NumberType::Float(float) if float.is_infinite() => {
+                write!(f, "{}", if *float > 0.0 { "inf" } else { "-inf" })
+            }
+            NumberType::Float(float) if float.fract() == 0.0 => write!(f, "{}.0", float),
             NumberType::Float(float) => write!(f, "{}", float),
             NumberType::Integer(int) => write!(f, "{}", int),
         }
@@ -247,7 +276,7 @@ impl FromStr for NumberType {
     }
 }
 
-impl<'literal> Display for Literal<'literal> {
+impl Display for Literal {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Literal::String(s) => write!(f, "{}", s),
@@ -258,7 +287,7 @@ impl<'literal> Display for Literal<'literal> {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Keyword {
     And,
     Class,
@@ -276,11 +305,17 @@ pub enum Keyword {
     True,
     Var,
     While,
+    Break,
+    Continue,
+    Throw,
+    Try,
+    Catch,
+    Finally,
+    Import,
     Eof,
     String,
     Integer,
     Float,
-    Bool,
 }
 
 impl Keyword {
@@ -302,11 +337,17 @@ impl Keyword {
             Keyword::True => "true".to_string(),
             Keyword::Var => "var".to_string(),
             Keyword::While => "while".to_string(),
+            Keyword::Break => "break".to_string(),
+            Keyword::Continue => "continue".to_string(),
+            Keyword::Throw => "throw".to_string(),
+            Keyword::Try => "try".to_string(),
+            Keyword::Catch => "catch".to_string(),
+            Keyword::Finally => "finally".to_string(),
+            Keyword::Import => "import".to_string(),
             Keyword::Eof => "eof".to_string(),
             Keyword::String => "string".to_string(),
             Keyword::Integer => "integer".to_string(),
             Keyword::Float => "float".to_string(),
-            Keyword::Bool => "bool".to_string(),
         }
     }
 }
@@ -332,6 +373,13 @@ impl FromStr for Keyword {
             "true" => Ok(Keyword::True),
             "var" => Ok(Keyword::Var),
             "while" => Ok(Keyword::While),
+            "break" => Ok(Keyword::Break),
+            "continue" => Ok(Keyword::Continue),
+            "throw" => Ok(Keyword::Throw),
+            "try" => Ok(Keyword::Try),
+            "catch" => Ok(Keyword::Catch),
+            "finally" => Ok(Keyword::Finally),
+            "import" => Ok(Keyword::Import),
             "eof" => Ok(Keyword::Eof),
             _ => Err(()),
         }
@@ -357,11 +405,17 @@ impl Display for Keyword {
             Keyword::True => write!(f, "true"),
             Keyword::Var => write!(f, "var"),
             Keyword::While => write!(f, "while"),
+            Keyword::Break => write!(f, "break"),
+            Keyword::Continue => write!(f, "continue"),
+            Keyword::Throw => write!(f, "throw"),
+            Keyword::Try => write!(f, "try"),
+            Keyword::Catch => write!(f, "catch"),
+            Keyword::Finally => write!(f, "finally"),
+            Keyword::Import => write!(f, "import"),
             Keyword::Eof => write!(f, "eof"),
             Keyword::String => write!(f, "string"),
-            Keyword::Integer => write!(f, "inter"),
+            Keyword::Integer => write!(f, "integer"),
             Keyword::Float => write!(f, "float"),
-            Keyword::Bool => write!(f, "bool"),
         }
     }
 }
@@ -0,0 +1,22 @@
+use crate::errors::{Error, ErrorType};
+use crate::Result;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub fn to_string<T>(node: &T) -> Result<String>
+where
+    T: Serialize + ?Sized,
+{
+    serde_json::to_string_pretty(node).map_err(to_error)
+}
+
+pub fn from_str<T>(source: &str) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    serde_json::from_str(source).map_err(to_error)
+}
+
+fn to_error(err: serde_json::Error) -> Error {
+    Error(ErrorType::Serialization(err.to_string()))
+}
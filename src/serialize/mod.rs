@@ -0,0 +1,56 @@
+use crate::errors::{Error, ErrorType};
+use crate::stmt::Stmt;
+use crate::Result;
+use std::path::Path;
+use std::str::FromStr;
+
+pub mod json;
+pub mod sexpr;
+
+/// The interchange formats a syntax tree can be exported to and imported from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AstFormat {
+    /// Lossless: every token keeps its type, lexeme, literal and line.
+    Json,
+    /// Structural only, e.g. `(* (- 123) (group 45.67))`. Tokens read back have line 0.
+    SExpr,
+}
+
+impl AstFormat {
+    pub fn from_path(path: &str) -> Option<Self> {
+        match Path::new(path).extension()?.to_str()? {
+            "json" => Some(AstFormat::Json),
+            "sexp" => Some(AstFormat::SExpr),
+            _ => None,
+        }
+    }
+
+    pub fn write(&self, statements: &[Stmt]) -> Result<String> {
+        match self {
+            AstFormat::Json => json::to_string(&statements),
+            AstFormat::SExpr => Ok(sexpr::print_program(statements)),
+        }
+    }
+
+    pub fn read(&self, source: &str) -> Result<Vec<Stmt>> {
+        match self {
+            AstFormat::Json => json::from_str(source),
+            AstFormat::SExpr => sexpr::parse_program(source),
+        }
+    }
+}
+
+impl FromStr for AstFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "json" => Ok(AstFormat::Json),
+            "sexpr" => Ok(AstFormat::SExpr),
+            _ => Err(Error(ErrorType::Serialization(format!(
+                "unknown ast format '{}', expected 'json' or 'sexpr'",
+                s
+            )))),
+        }
+    }
+}
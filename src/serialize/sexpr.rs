@@ -0,0 +1,712 @@
+use crate::errors::{Error, ErrorType};
+use crate::expr::{
+    Assign, Binary, Call, Comma, Conditional, ErrorExpr, Expr, FunctionExpr, Get, GroupingExpr,
+    Index, ListExpr, LiteralExpr, Logical, MapExpr, Set, SetIndex, Super, This, Unary, Variable,
+};
+use crate::scanner::token::Token;
+use crate::scanner::token_type::{Keyword, Literal, NumberType, SingleCharacter, TokenType};
+use crate::stmt::{
+    Block, Break, Catch, Class, Continue, ErrorStmt, Expression, Function, If, Import, Print,
+    Return, Stmt, Throw, Try, Var, While,
+};
+use crate::Result;
+use std::cell::Cell;
+use std::iter::Peekable;
+use std::rc::Rc;
+use std::str::{Chars, FromStr};
+
+// the s-expression format carries no locations, so every token read back gets this line
+const UNKNOWN_LINE: usize = 0;
+
+pub fn print_program(statements: &[Stmt]) -> String {
+    statements
+        .iter()
+        .map(print_stmt)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn print_stmt(stmt: &Stmt) -> String {
+    match stmt {
+        Stmt::Block(block) => parenthesize("block", block.statements.iter().map(print_stmt)),
+
+        Stmt::Break(..) => "(break)".to_string(),
+
+        Stmt::Class(class) => {
+            let mut parts = vec![class.name.lexeme().to_string()];
+
+            if let Some(superclass) = &class.superclass {
+                parts.push(parenthesize("<", [superclass.name.lexeme().to_string()]));
+            }
+
+            parts.extend(class.methods.iter().map(print_function));
+
+            parenthesize("class", parts)
+        }
+
+        Stmt::Continue(..) => "(continue)".to_string(),
+
+        Stmt::Error(error) => print_error(&error.message),
+
+        Stmt::Expression(expression) => parenthesize("expr", [print_expr(&expression.expression)]),
+
+        Stmt::Function(function) => print_function(function),
+
+        Stmt::If(if_stmt) => {
+            let mut parts = vec![
+                print_expr(&if_stmt.condition),
+                print_stmt(&if_stmt.then_branch),
+            ];
+
+            if let Some(else_branch) = &if_stmt.else_branch {
+                parts.push(print_stmt(else_branch));
+            }
+
+            parenthesize("if", parts)
+        }
+
+        Stmt::Import(import) => parenthesize(
+            "import",
+            [
+                print_literal(&Literal::String(import.path.clone())),
+                import.name.lexeme().to_string(),
+            ],
+        ),
+
+        Stmt::Print(print) => parenthesize("print", [print_expr(&print.expression)]),
+
+        Stmt::Return(return_stmt) => {
+            parenthesize("return", return_stmt.value.iter().map(print_expr))
+        }
+
+        Stmt::Throw(throw) => parenthesize("throw", [print_expr(&throw.value)]),
+
+        Stmt::Try(try_stmt) => {
+            let mut parts = vec![parenthesize("block", try_stmt.body.iter().map(print_stmt))];
+
+            if let Some(catch) = &try_stmt.catch {
+                let mut catch_parts = vec![catch.name.lexeme().to_string()];
+                catch_parts.extend(catch.body.iter().map(print_stmt));
+
+                parts.push(parenthesize("catch", catch_parts));
+            }
+
+            if let Some(finally) = &try_stmt.finally {
+                parts.push(parenthesize("finally", finally.iter().map(print_stmt)));
+            }
+
+            parenthesize("try", parts)
+        }
+
+        Stmt::Var(var) => {
+            let mut parts = vec![var.name.lexeme().to_string()];
+            parts.extend(var.initializer.iter().map(print_expr));
+
+            parenthesize("var", parts)
+        }
+
+        Stmt::While(while_stmt) => {
+            let mut parts = vec![
+                print_expr(&while_stmt.condition),
+                print_stmt(&while_stmt.body),
+            ];
+            parts.extend(while_stmt.increment.iter().map(print_expr));
+
+            parenthesize("while", parts)
+        }
+    }
+}
+
+pub fn print_expr(expr: &Expr) -> String {
+    match expr {
+        Expr::Assign(assign) => parenthesize(
+            "=",
+            [assign.name.lexeme().to_string(), print_expr(&assign.value)],
+        ),
+
+        Expr::Binary(binary) => parenthesize(
+            binary.operator.lexeme(),
+            [print_expr(&binary.left), print_expr(&binary.right)],
+        ),
+
+        Expr::Call(call) => parenthesize(
+            "call",
+            std::iter::once(print_expr(&call.callee)).chain(call.arguments.iter().map(print_expr)),
+        ),
+
+        Expr::Comma(comma) => parenthesize(
+            comma.operator.lexeme(),
+            [print_expr(&comma.left), print_expr(&comma.right)],
+        ),
+
+        Expr::Conditional(conditional) => parenthesize(
+            "?:",
+            [
+                print_expr(&conditional.condition),
+                print_expr(&conditional.then_branch),
+                print_expr(&conditional.else_branch),
+            ],
+        ),
+
+        Expr::Error(error) => print_error(&error.message),
+
+        Expr::Function(function) => {
+            let mut parts = vec![print_params(&function.params)];
+            parts.extend(function.body.iter().map(print_stmt));
+
+            parenthesize("fun", parts)
+        }
+
+        Expr::Get(get) => parenthesize(
+            "get",
+            [print_expr(&get.object), get.name.lexeme().to_string()],
+        ),
+
+        Expr::Grouping(grouping) => parenthesize("group", [print_expr(&grouping.expression)]),
+
+        Expr::Index(index) => parenthesize(
+            "index",
+            [print_expr(&index.object), print_expr(&index.index)],
+        ),
+
+        Expr::List(list) => parenthesize("list", list.elements.iter().map(print_expr)),
+
+        Expr::Literal(literal) => print_literal(&literal.value),
+
+        Expr::Logical(logical) => parenthesize(
+            logical.operator.lexeme(),
+            [print_expr(&logical.left), print_expr(&logical.right)],
+        ),
+
+        Expr::Map(map) => parenthesize(
+            "map",
+            map.entries
+                .iter()
+                .map(|(key, value)| parenthesize("", [print_expr(key), print_expr(value)])),
+        ),
+
+        Expr::Set(set) => parenthesize(
+            "set",
+            [
+                print_expr(&set.object),
+                set.name.lexeme().to_string(),
+                print_expr(&set.value),
+            ],
+        ),
+
+        Expr::SetIndex(set) => parenthesize(
+            "set-index",
+            [
+                print_expr(&set.object),
+                print_expr(&set.index),
+                print_expr(&set.value),
+            ],
+        ),
+
+        Expr::Super(s) => parenthesize("super", [s.method.lexeme().to_string()]),
+
+        Expr::This(..) => "this".to_string(),
+
+        Expr::Unary(unary) => parenthesize(unary.operator.lexeme(), [print_expr(&unary.right)]),
+
+        Expr::Variable(variable) => variable.name.lexeme().to_string(),
+    }
+}
+
+fn print_function(function: &Function) -> String {
+    let mut parts = vec![
+        function.name.lexeme().to_string(),
+        print_params(&function.params),
+    ];
+    parts.extend(function.body.iter().map(print_stmt));
+
+    parenthesize("fun", parts)
+}
+
+fn print_error(message: &str) -> String {
+    parenthesize(
+        "error",
+        [print_literal(&Literal::String(message.to_string()))],
+    )
+}
+
+fn print_params(params: &[Token]) -> String {
+    parenthesize("", params.iter().map(|param| param.lexeme()))
+}
+
+fn print_literal(literal: &Literal) -> String {
+    match literal {
+        Literal::String(s) => {
+            let mut quoted = String::from('"');
+
+            for c in s.chars() {
+                match c {
+                    '"' => quoted.push_str("\\\""),
+                    '\\' => quoted.push_str("\\\\"),
+                    '\n' => quoted.push_str("\\n"),
+                    _ => quoted.push(c),
+                }
+            }
+
+            quoted.push('"');
+            quoted
+        }
+        // debug formatting keeps the `.0` on whole floats so they read back as floats
+        Literal::Number(NumberType::Float(float)) => format!("{:?}", float),
+        _ => literal.build_string(),
+    }
+}
+
+fn parenthesize<I, S>(name: &str, parts: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut out = String::from('(');
+    out.push_str(name);
+
+    for part in parts {
+        if out.len() > 1 {
+            out.push(' ');
+        }
+
+        out.push_str(part.as_ref());
+    }
+
+    out.push(')');
+    out
+}
+
+pub fn parse_program(source: &str) -> Result<Vec<Stmt>> {
+    read_all(source)?.iter().map(to_stmt).collect()
+}
+
+#[derive(Debug)]
+enum SExpr {
+    Atom(String),
+    Str(String),
+    List(Vec<SExpr>),
+}
+
+fn read_all(source: &str) -> Result<Vec<SExpr>> {
+    let mut chars = source.chars().peekable();
+    let mut nodes = vec![];
+
+    while let Some(node) = read(&mut chars)? {
+        nodes.push(node);
+    }
+
+    Ok(nodes)
+}
+
+fn read(chars: &mut Peekable<Chars>) -> Result<Option<SExpr>> {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+    let c = match chars.next() {
+        Some(c) => c,
+        None => return Ok(None),
+    };
+
+    match c {
+        '(' => {
+            let mut items = vec![];
+
+            loop {
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+                if chars.next_if_eq(&')').is_some() {
+                    return Ok(Some(SExpr::List(items)));
+                }
+
+                match read(chars)? {
+                    Some(item) => items.push(item),
+                    None => return Err(error("unclosed '('".to_string())),
+                }
+            }
+        }
+        ')' => Err(error("unexpected ')'".to_string())),
+        '"' => {
+            let mut s = String::new();
+
+            loop {
+                match chars.next() {
+                    Some('"') => return Ok(Some(SExpr::Str(s))),
+                    Some('\\') => match chars.next() {
+                        Some('n') => s.push('\n'),
+                        Some(escaped @ ('"' | '\\')) => s.push(escaped),
+                        other => return Err(error(format!("invalid escape {:?}", other))),
+                    },
+                    Some(c) => s.push(c),
+                    None => return Err(error("unterminated string".to_string())),
+                }
+            }
+        }
+        _ => {
+            let mut atom = String::from(c);
+
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace() && *c != '(' && *c != ')') {
+                atom.push(c);
+            }
+
+            Ok(Some(SExpr::Atom(atom)))
+        }
+    }
+}
+
+fn to_stmt(node: &SExpr) -> Result<Stmt> {
+    let (head, args) = split_form(node)?;
+
+    match (head, args) {
+        ("block", statements) => Ok(Stmt::Block(Block {
+            statements: statements.iter().map(to_stmt).collect::<Result<_>>()?,
+        })),
+
+        ("break", []) => Ok(Stmt::Break(Break {
+            keyword: keyword_token(Keyword::Break),
+        })),
+
+        ("continue", []) => Ok(Stmt::Continue(Continue {
+            keyword: keyword_token(Keyword::Continue),
+        })),
+
+        ("class", [name, rest @ ..]) => {
+            let (superclass, methods) = match rest {
+                [SExpr::List(items), methods @ ..] if is_superclass(items) => (
+                    Some(Variable {
+                        name: to_identifier(&items[1])?,
+                        depth: Cell::default(),
+                    }),
+                    methods,
+                ),
+                methods => (None, methods),
+            };
+
+            Ok(Stmt::Class(Class {
+                name: to_identifier(name)?,
+                superclass,
+                methods: methods.iter().map(to_function).collect::<Result<_>>()?,
+            }))
+        }
+
+        ("error", [SExpr::Str(message)]) => Ok(Stmt::Error(ErrorStmt {
+            tokens: vec![],
+            message: message.clone(),
+        })),
+
+        ("expr", [expression]) => Ok(Stmt::Expression(Expression {
+            expression: to_expr(expression)?,
+        })),
+
+        ("fun", ..) => Ok(Stmt::Function(to_function(node)?)),
+
+        ("if", [condition, then_branch, else_branch @ ..]) if else_branch.len() <= 1 => {
+            Ok(Stmt::If(If {
+                condition: to_expr(condition)?,
+                then_branch: Box::new(to_stmt(then_branch)?),
+                else_branch: match else_branch.first() {
+                    Some(else_branch) => Some(Box::new(to_stmt(else_branch)?)),
+                    None => None,
+                },
+            }))
+        }
+
+        ("import", [SExpr::Str(path), name]) => Ok(Stmt::Import(Import {
+            keyword: keyword_token(Keyword::Import),
+            path: path.clone(),
+            name: to_identifier(name)?,
+        })),
+
+        ("print", [expression]) => Ok(Stmt::Print(Print {
+            expression: to_expr(expression)?,
+        })),
+
+        ("return", value) if value.len() <= 1 => Ok(Stmt::Return(Return {
+            keyword: keyword_token(Keyword::Return),
+            value: value.first().map(to_expr).transpose()?,
+        })),
+
+        ("throw", [value]) => Ok(Stmt::Throw(Throw {
+            keyword: keyword_token(Keyword::Throw),
+            value: to_expr(value)?,
+        })),
+
+        ("try", [body, clauses @ ..]) if !clauses.is_empty() && clauses.len() <= 2 => {
+            let body = match to_stmt(body)? {
+                Stmt::Block(block) => block.statements,
+                _ => return Err(error(format!("malformed try body {:?}", body))),
+            };
+
+            let mut catch = None;
+            let mut finally = None;
+
+            for clause in clauses {
+                match split_form(clause)? {
+                    ("catch", [name, statements @ ..]) if catch.is_none() && finally.is_none() => {
+                        catch = Some(Catch {
+                            name: to_identifier(name)?,
+                            body: statements.iter().map(to_stmt).collect::<Result<_>>()?,
+                        })
+                    }
+                    ("finally", statements) if finally.is_none() => {
+                        finally = Some(statements.iter().map(to_stmt).collect::<Result<_>>()?)
+                    }
+                    _ => return Err(error(format!("malformed try clause {:?}", clause))),
+                }
+            }
+
+            Ok(Stmt::Try(Try {
+                keyword: keyword_token(Keyword::Try),
+                body,
+                catch,
+                finally,
+            }))
+        }
+
+        ("var", [name, initializer @ ..]) if initializer.len() <= 1 => Ok(Stmt::Var(Var {
+            name: to_identifier(name)?,
+            initializer: initializer.first().map(to_expr).transpose()?,
+        })),
+
+        ("while", [condition, body, increment @ ..]) if increment.len() <= 1 => {
+            Ok(Stmt::While(While {
+                condition: to_expr(condition)?,
+                body: Box::new(to_stmt(body)?),
+                increment: increment.first().map(to_expr).transpose()?,
+            }))
+        }
+
+        _ => Err(error(format!("malformed statement {:?}", node))),
+    }
+}
+
+fn is_superclass(items: &[SExpr]) -> bool {
+    matches!(items, [SExpr::Atom(head), _] if head == "<")
+}
+
+fn to_function(node: &SExpr) -> Result<Function> {
+    match split_form(node)? {
+        ("fun", [name, SExpr::List(params), body @ ..]) => Ok(Function {
+            name: to_identifier(name)?,
+            params: params.iter().map(to_identifier).collect::<Result<_>>()?,
+            body: Rc::new(body.iter().map(to_stmt).collect::<Result<_>>()?),
+        }),
+        _ => Err(error(format!("malformed function {:?}", node))),
+    }
+}
+
+fn to_expr(node: &SExpr) -> Result<Expr> {
+    let items = match node {
+        SExpr::Str(s) => {
+            return Ok(Expr::Literal(LiteralExpr {
+                value: Literal::String(s.clone()),
+            }))
+        }
+        SExpr::Atom(atom) => return atom_to_expr(atom),
+        SExpr::List(items) => items,
+    };
+
+    let (head, args) = split_form(node)?;
+
+    match (head, args) {
+        ("=", [name, value]) => Ok(Expr::Assign(Assign {
+            name: to_identifier(name)?,
+            value: Box::new(to_expr(value)?),
+            depth: Cell::default(),
+        })),
+
+        ("call", [callee, arguments @ ..]) => Ok(Expr::Call(Call {
+            callee: Box::new(to_expr(callee)?),
+            parenthesis: single_character_token(SingleCharacter::RightParen),
+            arguments: arguments.iter().map(to_expr).collect::<Result<_>>()?,
+        })),
+
+        (",", [left, right]) => Ok(Expr::Comma(Comma {
+            left: Box::new(to_expr(left)?),
+            operator: single_character_token(SingleCharacter::Comma),
+            right: Box::new(to_expr(right)?),
+        })),
+
+        ("?:", [condition, then_branch, else_branch]) => Ok(Expr::Conditional(Conditional {
+            condition: Box::new(to_expr(condition)?),
+            then_branch: Box::new(to_expr(then_branch)?),
+            else_branch: Box::new(to_expr(else_branch)?),
+        })),
+
+        ("error", [SExpr::Str(message)]) => Ok(Expr::Error(ErrorExpr {
+            tokens: vec![],
+            message: message.clone(),
+        })),
+
+        ("fun", [SExpr::List(params), body @ ..]) => Ok(Expr::Function(FunctionExpr {
+            keyword: keyword_token(Keyword::Fun),
+            params: params.iter().map(to_identifier).collect::<Result<_>>()?,
+            body: Rc::new(body.iter().map(to_stmt).collect::<Result<_>>()?),
+        })),
+
+        ("get", [object, name]) => Ok(Expr::Get(Get {
+            object: Box::new(to_expr(object)?),
+            name: to_identifier(name)?,
+        })),
+
+        ("group", [expression]) => Ok(Expr::Grouping(GroupingExpr {
+            expression: Box::new(to_expr(expression)?),
+        })),
+
+        ("index", [object, index]) => Ok(Expr::Index(Index {
+            object: Box::new(to_expr(object)?),
+            bracket: single_character_token(SingleCharacter::RightBracket),
+            index: Box::new(to_expr(index)?),
+        })),
+
+        ("list", elements) => Ok(Expr::List(ListExpr {
+            bracket: single_character_token(SingleCharacter::LeftBracket),
+            elements: elements.iter().map(to_expr).collect::<Result<_>>()?,
+        })),
+
+        (operator @ ("and" | "or"), [left, right]) => Ok(Expr::Logical(Logical {
+            left: Box::new(to_expr(left)?),
+            operator: operator_token(operator)?,
+            right: Box::new(to_expr(right)?),
+        })),
+
+        ("set", [object, name, value]) => Ok(Expr::Set(Set {
+            object: Box::new(to_expr(object)?),
+            name: to_identifier(name)?,
+            value: Box::new(to_expr(value)?),
+        })),
+
+        ("map", entries) => Ok(Expr::Map(MapExpr {
+            brace: single_character_token(SingleCharacter::LeftBrace),
+            entries: entries
+                .iter()
+                .map(|entry| match entry {
+                    SExpr::List(pair) if pair.len() == 2 => {
+                        Ok((to_expr(&pair[0])?, to_expr(&pair[1])?))
+                    }
+                    _ => Err(error(format!("malformed map entry {:?}", entry))),
+                })
+                .collect::<Result<_>>()?,
+        })),
+
+        ("set-index", [object, index, value]) => Ok(Expr::SetIndex(SetIndex {
+            object: Box::new(to_expr(object)?),
+            bracket: single_character_token(SingleCharacter::RightBracket),
+            index: Box::new(to_expr(index)?),
+            value: Box::new(to_expr(value)?),
+        })),
+
+        ("super", [method]) => Ok(Expr::Super(Super {
+            keyword: keyword_token(Keyword::Super),
+            method: to_identifier(method)?,
+            depth: Cell::default(),
+        })),
+
+        (operator, [right]) => Ok(Expr::Unary(Unary {
+            operator: operator_token(operator)?,
+            right: Box::new(to_expr(right)?),
+        })),
+
+        (operator, [left, right]) => Ok(Expr::Binary(Binary {
+            left: Box::new(to_expr(left)?),
+            operator: operator_token(operator)?,
+            right: Box::new(to_expr(right)?),
+        })),
+
+        _ => Err(error(format!("malformed expression {:?}", items))),
+    }
+}
+
+fn atom_to_expr(atom: &str) -> Result<Expr> {
+    let value = match atom {
+        "true" => Literal::Bool(true),
+        "false" => Literal::Bool(false),
+        "nil" => Literal::Nil,
+        "this" => {
+            return Ok(Expr::This(This {
+                keyword: keyword_token(Keyword::This),
+                depth: Cell::default(),
+            }))
+        }
+        _ if atom.starts_with(|c: char| c.is_ascii_digit()) => {
+            let number = if atom.contains(['.', 'e', 'E']) {
+                atom.parse().map(NumberType::Float).ok()
+            } else {
+                atom.parse().map(NumberType::Integer).ok()
+            };
+
+            Literal::Number(number.ok_or_else(|| error(format!("invalid number '{}'", atom)))?)
+        }
+        _ => {
+            return Ok(Expr::Variable(Variable {
+                name: identifier_token(atom)?,
+                depth: Cell::default(),
+            }))
+        }
+    };
+
+    Ok(Expr::Literal(LiteralExpr { value }))
+}
+
+fn split_form(node: &SExpr) -> Result<(&str, &[SExpr])> {
+    match node {
+        SExpr::List(items) => match items.split_first() {
+            Some((SExpr::Atom(head), args)) => Ok((head.as_str(), args)),
+            _ => Err(error(format!("expected a form, found {:?}", node))),
+        },
+        _ => Err(error(format!("expected a form, found {:?}", node))),
+    }
+}
+
+fn to_identifier(node: &SExpr) -> Result<Token> {
+    match node {
+        SExpr::Atom(name) => identifier_token(name),
+        _ => Err(error(format!("expected an identifier, found {:?}", node))),
+    }
+}
+
+fn identifier_token(name: &str) -> Result<Token> {
+    let is_identifier = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && Keyword::from_str(name).is_err();
+
+    if !is_identifier {
+        return Err(error(format!("invalid identifier '{}'", name)));
+    }
+
+    Ok(Token::new(
+        TokenType::Identifier,
+        name,
+        Some(Literal::String(name.to_string())),
+        UNKNOWN_LINE,
+    ))
+}
+
+fn operator_token(operator: &str) -> Result<Token> {
+    match TokenType::from_str(operator) {
+        Ok(
+            token_type @ (TokenType::SingleCharacters(..)
+            | TokenType::SingleOrDoubles(..)
+            | TokenType::Keywords(Keyword::And | Keyword::Or)),
+        ) => Ok(Token::new(token_type, operator, None, UNKNOWN_LINE)),
+        _ => Err(error(format!("unknown operator '{}'", operator))),
+    }
+}
+
+fn keyword_token(keyword: Keyword) -> Token {
+    Token::new(
+        TokenType::Keywords(keyword),
+        keyword.build_string(),
+        None,
+        UNKNOWN_LINE,
+    )
+}
+
+fn single_character_token(character: SingleCharacter) -> Token {
+    let token_type = TokenType::SingleCharacters(character);
+
+    Token::new(token_type, token_type.build_string(), None, UNKNOWN_LINE)
+}
+
+fn error(message: String) -> Error {
+    Error(ErrorType::Serialization(message))
+}
@@ -0,0 +1,129 @@
+use crate::expr::{Expr, Variable};
+use crate::scanner::token::Token;
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Stmt {
+    Block(Block),
+    Break(Break),
+    Class(Class),
+    Continue(Continue),
+    Error(ErrorStmt),
+    Expression(Expression),
+    Function(Function),
+    If(If),
+    Import(Import),
+    Print(Print),
+    Return(Return),
+    Throw(Throw),
+    Try(Try),
+    Var(Var),
+    While(While),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Block {
+    pub statements: Vec<Stmt>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Break {
+    pub keyword: Token,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Class {
+    pub name: Token,
+    pub superclass: Option<Variable>,
+    pub methods: Vec<Function>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Continue {
+    pub keyword: Token,
+}
+
+/// Stands in for a statement that failed to parse. `tokens` is the span of input skipped while
+/// recovering from it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ErrorStmt {
+    pub tokens: Vec<Token>,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Expression {
+    pub expression: Expr,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Function {
+    pub name: Token,
+    pub params: Vec<Token>,
+    pub body: Rc<Vec<Stmt>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct If {
+    pub condition: Expr,
+    pub then_branch: Box<Stmt>,
+    pub else_branch: Option<Box<Stmt>>,
+}
+
+/// `import "path" as name;`. The path is relative to the file the import is written in.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Import {
+    pub keyword: Token,
+    pub path: String,
+    pub name: Token,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Print {
+    pub expression: Expr,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Return {
+    pub keyword: Token,
+    pub value: Option<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Throw {
+    pub keyword: Token,
+    pub value: Expr,
+}
+
+/// `try { body } catch (name) { ... } finally { ... }`, with at least one of the two clauses.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Try {
+    pub keyword: Token,
+    pub body: Vec<Stmt>,
+    pub catch: Option<Catch>,
+    /// Runs however the rest finishes, even by `return`, `break` or an error.
+    pub finally: Option<Vec<Stmt>>,
+}
+
+/// Handles an error thrown in a `try` body, with the thrown value bound to `name`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Catch {
+    pub name: Token,
+    pub body: Vec<Stmt>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Var {
+    pub name: Token,
+    pub initializer: Option<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct While {
+    pub condition: Expr,
+    pub body: Box<Stmt>,
+    /// The increment clause of a desugared `for` loop. It runs after every iteration, including
+    /// ones cut short by `continue`, which is why it isn't folded into `body`.
+    pub increment: Option<Expr>,
+}
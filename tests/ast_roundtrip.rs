@@ -0,0 +1,65 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/syntax.lox");
+
+fn emit(format: &str, path: &Path) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_rlox"))
+        .args(["--emit", format])
+        .arg(path)
+        .output()
+        .expect("failed to run rlox");
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    String::from_utf8(output.stdout).expect("output is not utf-8")
+}
+
+fn write_tmp(name: &str, contents: &str) -> PathBuf {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, contents).expect("failed to write temp file");
+
+    path
+}
+
+#[test]
+fn json_round_trip_is_lossless() {
+    let json = emit("json", Path::new(FIXTURE));
+    let reimported = emit("json", &write_tmp("roundtrip.json", &json));
+
+    assert_eq!(json, reimported);
+    assert!(json.contains(r#""lexeme": "greeting""#));
+    assert!(json.contains(r#""line": 2"#));
+}
+
+#[test]
+fn sexpr_round_trip() {
+    let sexpr = emit("sexpr", Path::new(FIXTURE));
+    let reimported = emit("sexpr", &write_tmp("roundtrip.sexp", &sexpr));
+
+    assert_eq!(sexpr, reimported);
+}
+
+#[test]
+fn json_and_sexpr_describe_the_same_tree() {
+    let sexpr = emit("sexpr", Path::new(FIXTURE));
+    let json = emit("json", Path::new(FIXTURE));
+    let from_json = emit("sexpr", &write_tmp("agree.json", &json));
+
+    assert_eq!(sexpr, from_json);
+}
+
+#[test]
+fn sexpr_matches_book_notation() {
+    let path = write_tmp("book.lox", "-123 * (45.67);");
+
+    assert_eq!(
+        emit("sexpr", &path).trim(),
+        "(expr (* (- 123) (group 45.67)))"
+    );
+}
@@ -0,0 +1,24 @@
+mod common;
+
+use common::run_script;
+
+#[test]
+fn unbounded_recursion_is_a_stack_overflow_error() {
+    let run = run_script("overflow.lox", "fun f(n) {\n  return f(n + 1);\n}\nf(0);\n");
+    assert_eq!(run.code, Some(70));
+
+    let lines: Vec<&str> = run.stderr.lines().collect();
+    assert!(lines[0].contains("2 at ) Stack overflow."));
+    assert!(lines[1].contains("at f (") && lines[1].ends_with("overflow.lox:2)"));
+    assert!(lines.contains(&"    [previous frame repeated 997 more times]"));
+    assert!(lines.last().unwrap().ends_with("overflow.lox:4)"));
+}
+
+#[test]
+fn recursion_under_the_limit_runs() {
+    let source = "fun f(n) {\n  if (n == 0) return 0;\n  return f(n - 1) + 1;\n}\nprint f(999);\n";
+
+    let run = run_script("deep.lox", source);
+    assert_eq!(run.code, Some(0));
+    assert_eq!(run.stdout, "999\n");
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::{output_of, run_script};
+
+const ANIMALS: &str = "class Animal {\n  init(name) {\n    this.name = name;\n  }\n  speak() {\n    return this.name + \" makes a sound\";\n  }\n  kind() {\n    return \"animal\";\n  }\n}\nclass Dog < Animal {\n  speak() {\n    return super.speak() + \", then barks\";\n  }\n}\n";
+
+#[test]
+fn subclasses_inherit_methods() {
+    let source = format!(
+        "{}print Dog(\"rex\").kind();\nprint Dog(\"rex\").name;\n",
+        ANIMALS
+    );
+
+    assert_eq!(output_of("inherit.lox", &source), "animal\nrex\n");
+}
+
+#[test]
+fn super_calls_the_superclass_method_on_the_same_instance() {
+    let source = format!("{}print Dog(\"rex\").speak();\n", ANIMALS);
+
+    assert_eq!(
+        output_of("super.lox", &source),
+        "rex makes a sound, then barks\n"
+    );
+}
+
+#[test]
+fn calling_init_again_returns_this() {
+    let source = format!(
+        "{}var a = Animal(\"cat\");\nprint a.init(\"lion\") == a;\nprint a.name;\n",
+        ANIMALS
+    );
+
+    assert_eq!(output_of("init_this.lox", &source), "true\nlion\n");
+}
+
+#[test]
+fn bound_methods_keep_their_instance() {
+    let source = format!(
+        "{}var cat = Animal(\"cat\");\nvar speak = cat.speak;\ncat.name = \"tom\";\nvar other = Animal(\"other\");\nother.speak = speak;\nprint speak();\nprint other.speak();\n",
+        ANIMALS
+    );
+
+    assert_eq!(
+        output_of("bound.lox", &source),
+        "tom makes a sound\ntom makes a sound\n"
+    );
+}
+
+#[test]
+fn superclasses_must_be_classes() {
+    let run = run_script(
+        "bad_superclass.lox",
+        "var NotAClass = \"nope\";\nclass A < NotAClass {}\n",
+    );
+
+    assert_eq!(run.code, Some(70));
+    assert!(run
+        .stderr
+        .contains("2 at NotAClass Superclass must be a class."));
+}
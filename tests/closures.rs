@@ -0,0 +1,110 @@
+mod common;
+
+use common::{output_of, run_script};
+
+#[test]
+fn counter_keeps_its_own_state() {
+    let source = r#"
+        fun makeCounter() {
+            var count = 0;
+            fun increment() {
+                count = count + 1;
+                return count;
+            }
+            return increment;
+        }
+
+        var first = makeCounter();
+        var second = makeCounter();
+        print first();
+        print first();
+        print second();
+        print first();
+    "#;
+
+    assert_eq!(output_of("counter.lox", source), "1\n2\n1\n3\n");
+}
+
+#[test]
+fn closures_share_a_captured_variable() {
+    let source = r#"
+        fun makePair() {
+            var value = 0;
+            fun set(n) { value = n; }
+            fun get() { return value; }
+            return [set, get];
+        }
+
+        var pair = makePair();
+        var set = pair[0];
+        var get = pair[1];
+        set(42);
+        print get();
+        set("changed");
+        print get();
+    "#;
+
+    assert_eq!(output_of("shared_upvalue.lox", source), "42\nchanged\n");
+}
+
+#[test]
+fn closure_in_loop_captures_a_fresh_body_variable_each_iteration() {
+    let source = r#"
+        var callbacks = [nil, nil, nil];
+        for (var i = 0; i < 3; i = i + 1) {
+            var captured = i;
+            callbacks[i] = fun () { return captured; };
+        }
+
+        print callbacks[0]();
+        print callbacks[1]();
+        print callbacks[2]();
+    "#;
+
+    assert_eq!(output_of("closure_in_loop.lox", source), "0\n1\n2\n");
+}
+
+// the `for` initializer declares one variable for the whole loop, the same as in the book
+#[test]
+fn closure_in_loop_shares_the_loop_variable() {
+    let source = r#"
+        var callbacks = [nil, nil, nil];
+        for (var i = 0; i < 3; i = i + 1) {
+            callbacks[i] = fun () { return i; };
+        }
+
+        print callbacks[0]();
+        print callbacks[2]();
+    "#;
+
+    assert_eq!(output_of("loop_variable.lox", source), "3\n3\n");
+}
+
+#[test]
+fn functions_outlive_the_scope_they_were_declared_in() {
+    let source = r#"
+        var greet;
+        {
+            var greeting = "hello";
+            greet = fun (name) { return greeting + " " + name; };
+        }
+
+        print greet("lox");
+        print greet;
+    "#;
+
+    assert_eq!(output_of("outlive.lox", source), "hello lox\n<fn>\n");
+}
+
+#[test]
+fn wrong_argument_count_is_an_error() {
+    let run = run_script("arity.lox", "fun add(a, b) { return a + b; }\nadd(1);");
+
+    assert_eq!(run.code, Some(70));
+    assert!(
+        run.stderr
+            .contains("2 at ) Expected 2 arguments but got 1."),
+        "{}",
+        run.stderr
+    );
+}
@@ -0,0 +1,80 @@
+// shared by every test binary, and none of them uses all of it
+#![allow(dead_code)]
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+pub struct Run {
+    pub stdout: String,
+    pub stderr: String,
+    pub code: Option<i32>,
+}
+
+// runs `source` as a script file named `name` in the test temp directory
+pub fn run_script(name: &str, source: &str) -> Run {
+    run_script_with(&[], name, source)
+}
+
+// runs `source` as a script that has to succeed, and gives back what it printed
+pub fn output_of(name: &str, source: &str) -> String {
+    let run = run_script(name, source);
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+
+    run.stdout
+}
+
+// like `run_script`, with `options` passed before the script
+pub fn run_script_with(options: &[&str], name: &str, source: &str) -> Run {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, source).expect("failed to write temp file");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rlox"))
+        .args(options)
+        .arg(&path)
+        .output()
+        .expect("failed to run rlox");
+
+    finished(output)
+}
+
+// like `run_script`, with `input` piped to the script's stdin
+pub fn run_script_with_input(name: &str, source: &str, input: &str) -> Run {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, source).expect("failed to write temp file");
+
+    run_piped(&[path.to_str().expect("temp path is not utf-8")], input)
+}
+
+// types `input` at the prompt, started with `options`
+pub fn run_prompt(options: &[&str], input: &str) -> Run {
+    run_piped(options, input)
+}
+
+fn run_piped(args: &[&str], input: &str) -> Run {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rlox"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run rlox");
+
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(input.as_bytes())
+        .expect("failed to write to rlox");
+
+    finished(child.wait_with_output().expect("failed to run rlox"))
+}
+
+fn finished(output: Output) -> Run {
+    Run {
+        stdout: String::from_utf8(output.stdout).expect("output is not utf-8"),
+        stderr: String::from_utf8(output.stderr).expect("output is not utf-8"),
+        code: output.status.code(),
+    }
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use common::{output_of, run_script};
+
+#[test]
+fn conditionals_nest_to_the_right() {
+    let source = "print true ? 1 : false ? 2 : 3;\nprint false ? 1 : false ? 2 : 3;\nprint false ? 1 : true ? 2 : 3;\n";
+
+    assert_eq!(output_of("right_assoc.lox", source), "1\n3\n2\n");
+}
+
+#[test]
+fn conditionals_bind_tighter_than_assignment_and_looser_than_or() {
+    let source = "var a;\na = false ? 1 : 2;\nprint a;\nprint nil or false ? \"yes\" : \"no\";\n";
+
+    assert_eq!(output_of("precedence.lox", source), "2\nno\n");
+}
+
+#[test]
+fn comma_evaluates_both_sides_and_keeps_the_right() {
+    let source = "var a = 0;\nprint (a = 1, a + 1);\nprint a;\n";
+
+    assert_eq!(output_of("comma.lox", source), "2\n1\n");
+}
+
+#[test]
+fn a_missing_colon_is_reported_at_the_token_in_its_place() {
+    let run = run_script("missing_colon.lox", "var a = 1;\nprint a ? 1 2;\n");
+
+    assert_eq!(run.code, Some(65));
+    assert!(
+        run.stderr
+            .contains("2 at 2 Expect ':' after then branch of conditional expression."),
+        "{}",
+        run.stderr
+    );
+}
@@ -0,0 +1,153 @@
+use rlox::errors::LimitExceeded;
+use rlox::interpreter::limits::Limits;
+use rlox::{Error, ErrorType, IntoValue, Lox, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+#[test]
+fn eval_returns_the_last_expression() {
+    let lox = Lox::new();
+
+    let value = lox.eval("var x = 20;\nx + 22;").unwrap();
+
+    assert_eq!(value, 42.into_value());
+    assert_eq!(lox.eval("print x;").unwrap(), Value::Nil);
+}
+
+#[test]
+fn globals_are_shared_with_rust() {
+    let lox = Lox::new();
+
+    lox.set_global("names", vec!["ada", "grace"]).unwrap();
+    lox.eval("var count = len(names);\nvar first = names[0];")
+        .unwrap();
+
+    assert_eq!(lox.get_global::<i64>("count").unwrap(), 2);
+    assert_eq!(lox.get_global::<String>("first").unwrap(), "ada");
+    assert_eq!(
+        lox.get_global::<Vec<String>>("names").unwrap(),
+        vec!["ada", "grace"]
+    );
+
+    let err = lox.get_global::<bool>("first").unwrap_err();
+    assert!(err.to_string().contains("Expected a boolean, got string."));
+}
+
+#[test]
+fn lox_functions_are_callable_from_rust() {
+    let lox = Lox::new();
+    lox.eval("fun greet(name, times) {\n  var greeting = {};\n  greeting[name] = times * 2;\n  return greeting;\n}\nfun fail() {\n  return nil + 1;\n}")
+        .unwrap();
+
+    let greeting: HashMap<String, i64> = lox
+        .call("greet", vec!["lox".into_value(), 21.into_value()])
+        .unwrap();
+    assert_eq!(greeting.get("lox"), Some(&42));
+
+    let err = lox.call::<Value>("greet", vec![]).unwrap_err();
+    assert!(err.to_string().contains("Expected 2 arguments but got 0."));
+
+    match lox.call::<Value>("fail", vec![]).unwrap_err().0 {
+        ErrorType::Interpreter(error) => {
+            assert_eq!(error.line, Some(7));
+            assert_eq!(error.trace[0].function, "fail");
+            assert_eq!(error.trace.len(), 1);
+        }
+        err => panic!("expected a runtime error, got {}", err),
+    }
+}
+
+#[test]
+fn run_file_runs_scripts_and_their_imports() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("embedding");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("config.lox"), "var port = 8080;\n").unwrap();
+    fs::write(
+        dir.join("main.lox"),
+        "import \"config.lox\" as config;\nvar port = config.port + 1;\n",
+    )
+    .unwrap();
+
+    let lox = Lox::new();
+    lox.run_file(dir.join("main.lox")).unwrap();
+
+    assert_eq!(lox.get_global::<usize>("port").unwrap(), 8081);
+}
+
+#[test]
+fn limits_cover_the_whole_snippet() {
+    let lox = Lox::new();
+    lox.set_limits(Limits {
+        max_steps: Some(100),
+        ..Limits::default()
+    });
+    lox.eval("var total = 0;\nvar i;").unwrap();
+
+    let one_loop = "i = 0;\nwhile (i < 30) { i = i + 1; total = total + 1; }\n";
+    let err = lox.eval(&one_loop.repeat(50)).unwrap_err();
+    assert!(matches!(
+        err,
+        Error(ErrorType::Limit(LimitExceeded::Steps(100)))
+    ));
+
+    // the engine is still usable, with the whole allowance for the next snippet
+    assert!(lox.get_global::<i64>("total").unwrap() < 100);
+    assert_eq!(
+        lox.eval("total = 0;\ni = 0;\ntotal;").unwrap(),
+        0.into_value()
+    );
+}
+
+#[test]
+fn values_from_rust_count_toward_the_memory_limit() {
+    let lox = Lox::new();
+    lox.set_limits(Limits {
+        max_memory: Some(100000),
+        ..Limits::default()
+    });
+
+    let before = lox.interpreter().memory_usage();
+    lox.set_global("small", vec![vec!["some text"; 10]; 10])
+        .unwrap();
+    assert!(lox.interpreter().memory_usage() >= before + 100 * "some text".len());
+
+    let err = lox.set_global("big", vec!["some text"; 10000]).unwrap_err();
+    assert!(matches!(
+        err,
+        Error(ErrorType::Limit(LimitExceeded::Memory(100000)))
+    ));
+}
+
+#[test]
+fn recursion_up_to_the_call_depth_limit_fits_on_the_callers_stack() {
+    let lox = Lox::new();
+
+    let value = lox
+        .eval("fun f(n) {\n  if (n > 0) return f(n - 1) + 1;\n  return 0;\n}\nf(999);")
+        .unwrap();
+    assert_eq!(value, 999.into_value());
+
+    let err = lox
+        .eval("fun g(n) {\n  return g(n + 1);\n}\ng(0);")
+        .unwrap_err();
+    assert!(err.to_string().contains("2 at ) Stack overflow."));
+}
+
+#[test]
+fn only_eval_each_reports_unfinished_input_as_incomplete() {
+    let lox = Lox::new();
+
+    assert!(matches!(
+        lox.eval("var s = \"abc"),
+        Err(Error(ErrorType::Scanner(_)))
+    ));
+    assert!(matches!(
+        lox.eval("if (true) {"),
+        Err(Error(ErrorType::Parse(_)))
+    ));
+    assert!(matches!(
+        lox.eval_each("if (true) {", |_| {}),
+        Err(Error(ErrorType::Incomplete(_)))
+    ));
+}
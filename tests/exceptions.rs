@@ -0,0 +1,58 @@
+mod common;
+
+use common::run_script;
+
+fn run_ok(name: &str, source: &str) -> String {
+    let run = run_script(name, source);
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+
+    run.stdout
+}
+
+#[test]
+fn thrown_values_are_caught() {
+    let source = "try {\n  throw {\"code\": 42};\n} catch (e) {\n  print e[\"code\"];\n}\n";
+
+    assert_eq!(run_ok("throw.lox", source), "42\n");
+}
+
+#[test]
+fn runtime_errors_are_caught_as_error_objects() {
+    let source = "fun risky(x) {\n  return x + nil;\n}\ntry {\n  risky(1);\n} catch (e) {\n  print e.message;\n  print e.line;\n  print e.trace;\n}\n";
+
+    let stdout = run_ok("error_object.lox", source);
+    let lines: Vec<&str> = stdout.lines().collect();
+
+    assert_eq!(lines[0], "Operands must be two numbers or two strings.");
+    assert_eq!(lines[1], "2");
+    assert!(lines[2].starts_with("[\"risky (") && lines[2].contains("error_object.lox:2)\", "));
+    assert!(lines[2].ends_with("error_object.lox:5)\"]"));
+}
+
+#[test]
+fn finally_runs_on_return_break_and_throw() {
+    let source = "fun returns() {\n  try {\n    return \"returned\";\n  } finally {\n    print \"after return\";\n  }\n}\nprint returns();\nwhile (true) {\n  try {\n    break;\n  } finally {\n    print \"after break\";\n  }\n}\ntry {\n  try {\n    throw \"thrown\";\n  } finally {\n    print \"after throw\";\n  }\n} catch (e) {\n  print e;\n}\n";
+
+    assert_eq!(
+        run_ok("finally.lox", source),
+        "after return\nreturned\nafter break\nafter throw\nthrown\n"
+    );
+}
+
+#[test]
+fn an_error_caught_in_finally_doesnt_replace_the_one_being_thrown() {
+    let source = "try {\n  try {\n    throw \"first\";\n  } finally {\n    try {\n      throw \"second\";\n    } catch (e) {}\n  }\n} catch (e) {\n  print e;\n}\n";
+
+    assert_eq!(run_ok("nested.lox", source), "first\n");
+}
+
+#[test]
+fn uncaught_throws_stop_the_script() {
+    let run = run_script("uncaught.lox", "print 1;\nthrow \"boom\";\nprint 2;\n");
+
+    assert_eq!(run.code, Some(70));
+    assert_eq!(run.stdout, "1\n");
+    assert!(run
+        .stderr
+        .starts_with("Interpreter error `2 at throw boom`"));
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use common::run_script;
+
+const NODE: &str = "class Node {\n  init(name) {\n    this.name = name;\n  }\n}\n";
+
+// runs `make` a hundred times, and prints whether that left objects behind before and after a
+// collection
+fn leaves_garbage(make: &str) -> String {
+    let source = format!(
+        "{}{}\ngc();\nvar before = gcStats()[\"live_objects\"];\nvar i = 0;\nwhile (i < 100) {{\n  make();\n  i = i + 1;\n}}\nprint gcStats()[\"live_objects\"] > before;\nprint gc() > 0;\nprint gcStats()[\"live_objects\"] == before;\n",
+        NODE, make
+    );
+
+    let run = run_script("gc.lox", &source);
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+
+    run.stdout
+}
+
+#[test]
+fn instances_referring_to_each_other_are_freed() {
+    let make = "fun make() {\n  var a = Node(\"a\");\n  var b = Node(\"b\");\n  a.next = b;\n  b.next = a;\n}";
+
+    assert_eq!(leaves_garbage(make), "true\ntrue\ntrue\n");
+}
+
+#[test]
+fn a_closure_stored_on_what_it_captures_is_freed() {
+    let make =
+        "fun make() {\n  var node = Node(\"n\");\n  node.callback = fun () { return node; };\n}";
+
+    assert_eq!(leaves_garbage(make), "true\ntrue\ntrue\n");
+}
+
+#[test]
+fn a_list_containing_itself_is_freed() {
+    let make = "fun make() {\n  var list = [nil];\n  list[0] = list;\n}";
+
+    assert_eq!(leaves_garbage(make), "true\ntrue\ntrue\n");
+}
+
+#[test]
+fn cycles_still_in_use_are_kept() {
+    let source = format!(
+        "{}var node = Node(\"kept\");\nnode.next = node;\nfun make() {{\n  var inner = Node(\"inner\");\n  inner.self = inner;\n  gc();\n  return inner;\n}}\nvar made = make();\ngc();\nprint node.next.next.name;\nprint made.self.name;\nprint gcStats()[\"collections\"];\n",
+        NODE
+    );
+
+    let run = run_script("gc_kept.lox", &source);
+
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(run.stdout, "kept\ninner\n2\n");
+}
@@ -0,0 +1,25 @@
+mod common;
+
+use common::output_of;
+
+#[test]
+fn lambdas_are_called_like_named_functions() {
+    let source = "var add = fun (a, b) { return a + b; };\nprint add(1, 2);\nprint fun (x) { return x * 2; }(4);\n";
+
+    assert_eq!(output_of("call.lox", source), "3\n8\n");
+}
+
+#[test]
+fn lambdas_can_be_passed_as_arguments() {
+    let source =
+        "fun twice(f, x) { return f(f(x)); }\nprint twice(fun (n) { return n + 3; }, 1);\n";
+
+    assert_eq!(output_of("callback.lox", source), "7\n");
+}
+
+#[test]
+fn lambdas_capture_the_enclosing_scope() {
+    let source = "fun counter() {\n  var count = 0;\n  return fun () {\n    count = count + 1;\n    return count;\n  };\n}\nvar next = counter();\nnext();\nprint next();\nvar other = counter();\nprint other();\nvar n = 10;\nvar read = fun () { return n; };\nn = 11;\nprint read();\n";
+
+    assert_eq!(output_of("capture.lox", source), "2\n1\n11\n");
+}
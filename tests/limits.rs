@@ -0,0 +1,55 @@
+mod common;
+
+use common::{run_prompt, run_script_with};
+
+const SPIN: &str = "var i = 0;\nwhile (true) {\n  i = i + 1;\n}\n";
+
+#[test]
+fn going_over_the_step_limit_stops_the_script() {
+    let run = run_script_with(&["--max-steps", "50"], "steps.lox", SPIN);
+
+    assert_eq!(run.code, Some(70));
+    assert_eq!(run.stderr, "Limit exceeded `Ran more than 50 steps.`\n");
+}
+
+#[test]
+fn going_over_the_timeout_stops_the_script() {
+    let run = run_script_with(&["--timeout", "0.2"], "timeout.lox", SPIN);
+
+    assert_eq!(run.code, Some(70));
+    assert_eq!(run.stderr, "Limit exceeded `Ran longer than 200ms.`\n");
+}
+
+#[test]
+fn the_interpreter_runs_the_next_input_after_a_limit() {
+    let input = "var n = 1;\nwhile (true) n = n + 1;\nprint n > 1;\n";
+
+    let run = run_prompt(&["--max-steps", "20"], input);
+
+    assert_eq!(run.code, Some(0));
+    assert!(run.stderr.contains("Ran more than 20 steps."));
+    assert!(run.stdout.contains("true\n"));
+}
+
+#[test]
+fn growing_past_the_memory_limit_is_out_of_memory() {
+    let source = "var s = \"ab\";\nwhile (true) s = s + s;\n";
+
+    let run = run_script_with(&["--max-memory", "100000"], "grow.lox", source);
+
+    assert_eq!(run.code, Some(70));
+    assert_eq!(
+        run.stderr,
+        "Limit exceeded `Out of memory, using more than 100000 bytes.`\n"
+    );
+}
+
+#[test]
+fn memory_that_has_been_freed_doesnt_count() {
+    let source = "var i = 0;\nwhile (i < 10000) {\n  var item = [i, \"some text\", {\"i\": i}];\n  print item[0] == i;\n  i = i + 1;\n}\n";
+
+    let run = run_script_with(&["--max-memory", "100000"], "churn.lox", source);
+
+    assert_eq!(run.code, Some(0));
+    assert_eq!(run.stdout.lines().count(), 10000);
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use common::run_script;
+
+#[test]
+fn integral_floats_index_lists() {
+    let run = run_script(
+        "list_float_index.lox",
+        "var xs = [10, 20, 30];\nprint xs[6 / 3];\nxs[2.0] = 31;\nprint xs[2];\n",
+    );
+
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(run.stdout, "30\n31\n");
+}
+
+#[test]
+fn bad_indexes_are_runtime_errors() {
+    let cases = [
+        ("xs[1.5]", "List index must be an integer, got 1.5."),
+        ("xs[-1]", "List index can't be negative, got -1."),
+        ("xs[3]", "List index 3 is out of bounds for length 3."),
+        ("m[\"k\"]", "Map has no key \"k\"."),
+    ];
+
+    // list and map errors both point at the `]`
+    for (expression, message) in cases {
+        let source = format!(
+            "var xs = [1, 2, 3];\nvar m = {{}};\nprint {};\n",
+            expression
+        );
+        let run = run_script("list_bad_index.lox", &source);
+
+        assert_eq!(run.code, Some(70), "{}", expression);
+        assert!(
+            run.stderr.contains(&format!("3 at ] {}", message)),
+            "{}: {}",
+            expression,
+            run.stderr
+        );
+    }
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use common::{output_of, run_script};
+
+#[test]
+fn continue_in_a_for_loop_still_runs_the_increment() {
+    let source = "for (var i = 0; i < 6; i = i + 1) {\n  if (i == 2) continue;\n  if (i == 4) break;\n  print i;\n}\n";
+
+    assert_eq!(output_of("for_continue.lox", source), "0\n1\n3\n");
+}
+
+#[test]
+fn break_and_continue_work_in_while_loops() {
+    let source = "var i = 0;\nwhile (true) {\n  i = i + 1;\n  if (i == 2) continue;\n  if (i > 3) break;\n  print i;\n}\n";
+
+    assert_eq!(output_of("while.lox", source), "1\n3\n");
+}
+
+#[test]
+fn break_and_continue_outside_a_loop_are_static_errors() {
+    let cases = [
+        (
+            "print 1;\nbreak;\n",
+            "2 at break Can't use 'break' outside of a loop.",
+        ),
+        (
+            "while (false) {}\nfun f() {\n  continue;\n}\n",
+            "3 at continue Can't use 'continue' outside of a loop.",
+        ),
+    ];
+
+    for (source, message) in cases {
+        let run = run_script("outside_loop.lox", source);
+
+        assert_eq!(run.code, Some(65), "{}", source);
+        assert!(run.stdout.is_empty(), "{}", run.stdout);
+        assert!(run.stderr.contains(message), "{}", run.stderr);
+    }
+}
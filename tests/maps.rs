@@ -0,0 +1,26 @@
+mod common;
+
+use common::output_of;
+
+#[test]
+fn braces_are_a_map_in_an_expression_and_a_block_as_a_statement() {
+    let source =
+        "var empty = {};\nprint empty;\n{\n  print \"block\";\n}\nprint {\"k\": 1}[\"k\"];\n";
+
+    assert_eq!(output_of("braces.lox", source), "{}\nblock\n1\n");
+}
+
+#[test]
+fn integer_and_float_keys_that_are_equal_are_the_same_key() {
+    let source = "var m = {1: \"one\"};\nprint m[1.0];\nm[2.0] = \"two\";\nprint m[2];\nm[1.0] = \"uno\";\nprint m[1];\n";
+
+    assert_eq!(output_of("number_keys.lox", source), "one\ntwo\nuno\n");
+}
+
+#[test]
+fn strings_booleans_and_nil_are_keys() {
+    let source =
+        "var m = {\"a\": 1, true: 2, nil: 3};\nprint m[\"a\"];\nprint m[true];\nprint m[nil];\n";
+
+    assert_eq!(output_of("keys.lox", source), "1\n2\n3\n");
+}
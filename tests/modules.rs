@@ -0,0 +1,105 @@
+mod common;
+
+use common::run_script;
+use std::fs;
+use std::path::Path;
+
+// writes the modules a test's script imports, in a directory of its own
+fn write_modules(dir: &str, modules: &[(&str, &str)]) {
+    for (name, source) in modules {
+        let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(dir).join(name);
+        fs::create_dir_all(path.parent().expect("modules are in a directory"))
+            .expect("failed to create module directory");
+        fs::write(&path, source).expect("failed to write module");
+    }
+}
+
+#[test]
+fn modules_run_once_and_share_their_globals() {
+    write_modules(
+        "modules_once",
+        &[(
+            "lib/counter.lox",
+            "print \"loading\";\nvar count = 0;\nfun bump() {\n  count = count + 1;\n}\n",
+        )],
+    );
+
+    let source = "import \"lib/counter.lox\" as counter;\nimport \"lib/counter.lox\" as again;\ncounter.bump();\nprint again.count;\nprint counter;\n";
+    let run = run_script("modules_once/main.lox", source);
+
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(run.stdout, "loading\n1\n<module counter>\n");
+}
+
+#[test]
+fn imports_resolve_relative_to_the_importing_file() {
+    write_modules(
+        "modules_relative",
+        &[
+            (
+                "lib/greeting.lox",
+                "import \"words.lox\" as words;\nfun greet(name) {\n  return words.hello + \" \" + name;\n}\n",
+            ),
+            ("lib/words.lox", "var hello = \"hello\";\n"),
+        ],
+    );
+
+    let source = "import \"lib/greeting.lox\" as greeting;\nprint greeting.greet(\"lox\");\n";
+    let run = run_script("modules_relative/main.lox", source);
+
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(run.stdout, "hello lox\n");
+}
+
+#[test]
+fn missing_declarations_are_runtime_errors() {
+    write_modules("modules_missing", &[("empty.lox", "")]);
+
+    let run = run_script(
+        "modules_missing/main.lox",
+        "import \"empty.lox\" as empty;\nprint empty.nothing;\n",
+    );
+
+    assert_eq!(run.code, Some(70));
+    assert!(run
+        .stderr
+        .contains("2 at nothing Module 'empty' has no declaration 'nothing'."));
+}
+
+#[test]
+fn circular_imports_show_the_chain() {
+    write_modules(
+        "modules_circular",
+        &[
+            ("a.lox", "import \"b.lox\" as b;\n"),
+            ("b.lox", "import \"main.lox\" as main;\n"),
+        ],
+    );
+
+    let run = run_script("modules_circular/main.lox", "import \"a.lox\" as a;\n");
+
+    assert_eq!(run.code, Some(70));
+    assert!(run.stderr.contains("main.lox -> "), "{}", run.stderr);
+    assert!(run.stderr.contains("a.lox -> "), "{}", run.stderr);
+    assert!(run.stderr.contains("b.lox -> "), "{}", run.stderr);
+    assert!(run.stderr.contains("main.lox."), "{}", run.stderr);
+}
+
+#[test]
+fn warnings_in_modules_are_reported() {
+    write_modules(
+        "modules_warning",
+        &[("lib.lox", "fun f() {\n  var unused = 1;\n}\n")],
+    );
+
+    let run = run_script("modules_warning/main.lox", "import \"lib.lox\" as lib;\n");
+
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert!(run.stderr.starts_with("Warning `"), "{}", run.stderr);
+    assert!(
+        run.stderr
+            .contains("lib.lox: 2 at unused Local variable 'unused' is never used."),
+        "{}",
+        run.stderr
+    );
+}
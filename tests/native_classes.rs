@@ -0,0 +1,145 @@
+use rlox::errors::LimitExceeded;
+use rlox::interpreter::callable::Arity;
+use rlox::interpreter::limits::Limits;
+use rlox::{Error, ErrorType, FromValue, IntoValue, Lox, NativeClass, Value};
+use std::collections::HashMap;
+
+struct Request {
+    path: String,
+    headers: HashMap<String, String>,
+    status: i64,
+}
+
+fn request_class() -> NativeClass {
+    NativeClass::builder::<Request>("Request")
+        .constructor(Arity::Fixed(1), |_, arguments| {
+            Ok(Request {
+                path: String::from_value(arguments[0].clone())?,
+                headers: HashMap::new(),
+                status: 200,
+            })
+        })
+        .method("header", Arity::Fixed(1), |_, request, arguments| {
+            let name = String::from_value(arguments[0].clone())?;
+
+            Ok(request.headers.get(&name).cloned().into_value())
+        })
+        .method_mut("setHeader", Arity::Fixed(2), |_, request, arguments| {
+            let name = String::from_value(arguments[0].clone())?;
+            let value = String::from_value(arguments[1].clone())?;
+            request.headers.insert(name, value);
+
+            Ok(Value::Nil)
+        })
+        .getter("path", |request| request.path.clone())
+        .getter("status", |request| request.status)
+        .setter("status", |request, status: i64| request.status = status)
+        .build()
+}
+
+fn lox_with_request() -> Lox {
+    let lox = Lox::new();
+    lox.define_class(request_class());
+
+    lox
+}
+
+#[test]
+fn host_objects_have_methods_and_properties() {
+    let lox = lox_with_request();
+    let request = lox
+        .wrap(Request {
+            path: String::from("/index"),
+            headers: HashMap::from([(String::from("host"), String::from("lox.dev"))]),
+            status: 200,
+        })
+        .unwrap();
+    lox.set_global("request", request).unwrap();
+
+    let value = lox
+        .eval("request.status = 404;\nrequest.path + \" on \" + request.header(\"host\");")
+        .unwrap();
+
+    assert_eq!(value, "/index on lox.dev".into_value());
+    assert_eq!(
+        lox.eval("request.header(\"missing\");").unwrap(),
+        Value::Nil
+    );
+    assert_eq!(
+        lox.eval("request;").unwrap().to_string(),
+        "Request instance"
+    );
+
+    let request = lox.get_global::<Value>("request").unwrap();
+    assert_eq!(request.downcast::<Request>().unwrap().status, 404);
+    assert!(request.downcast::<String>().is_none());
+}
+
+#[test]
+fn lox_can_construct_host_objects() {
+    let lox = lox_with_request();
+
+    lox.eval("var request = Request(\"/about\");\nrequest.setHeader(\"accept\", \"text/html\");")
+        .unwrap();
+
+    let request = lox.get_global::<Value>("request").unwrap();
+    let request = request.downcast::<Request>().unwrap();
+    assert_eq!(request.path, "/about");
+    assert_eq!(request.headers["accept"], "text/html");
+}
+
+#[test]
+fn misuse_is_a_runtime_error() {
+    let lox = lox_with_request();
+    lox.eval("var request = Request(\"/\");").unwrap();
+
+    let errors = [
+        ("request.missing;", "Undefined property 'missing'."),
+        (
+            "request.path = \"/other\";",
+            "Can't set property 'path' on Request.",
+        ),
+        (
+            "request.status = \"ok\";",
+            "Expected an integer, got string.",
+        ),
+        ("request.header(1);", "Expected a string, got number."),
+        ("Request(1);", "Expected a string, got number."),
+    ];
+
+    for (source, message) in errors {
+        let err = lox.eval(source).unwrap_err().to_string();
+        assert!(err.contains(message), "{}: {}", source, err);
+    }
+
+    assert!(lox.wrap(String::from("not a class")).is_err());
+}
+
+#[test]
+fn host_objects_count_toward_the_memory_limit() {
+    let lox = lox_with_request();
+    lox.set_limits(Limits {
+        max_memory: Some(20000),
+        ..Limits::default()
+    });
+
+    let before = lox.interpreter().memory_usage();
+    lox.eval("var request = Request(\"/\");").unwrap();
+    assert!(lox.interpreter().memory_usage() > before);
+
+    let mut kept = vec![];
+    let err = loop {
+        match lox.wrap(Request {
+            path: String::from("/"),
+            headers: HashMap::new(),
+            status: 200,
+        }) {
+            Ok(request) => kept.push(request),
+            Err(err) => break err,
+        }
+    };
+    assert!(matches!(
+        err,
+        Error(ErrorType::Limit(LimitExceeded::Memory(20000)))
+    ));
+}
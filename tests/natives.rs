@@ -0,0 +1,80 @@
+mod common;
+
+use common::{output_of, run_script, run_script_with_input};
+
+#[test]
+fn clock_counts_seconds() {
+    let source = "var start = clock();\nprint type(start);\nprint start > 1000000000;\nprint clock() >= start;\n";
+
+    assert_eq!(output_of("clock.lox", source), "number\ntrue\ntrue\n");
+}
+
+#[test]
+fn str_and_type_describe_values() {
+    let source = "print str(1) + str(2.5) + str(nil) + str(true) + str([1, \"a\"]);\nprint type(nil);\nprint type(false);\nprint type(1);\nprint type(\"\");\nprint type([]);\nprint type({});\nprint type(clock);\nclass A {}\nprint type(A);\nprint type(A());\n";
+
+    assert_eq!(
+        output_of("str_type.lox", source),
+        "12.5niltrue[1, \"a\"]\nnil\nboolean\nnumber\nstring\nlist\nmap\nfunction\nclass\ninstance\n"
+    );
+}
+
+#[test]
+fn num_follows_the_literal_rules() {
+    let source = "print num(\"42\");\nprint num(\" 2.5 \");\nprint num(\"-7\");\nprint num(\"99999999999999999999\");\nprint num(3);\n";
+
+    assert_eq!(
+        output_of("num.lox", source),
+        "42\n2.5\n-7\n100000000000000000000.0\n3\n"
+    );
+
+    for text in ["nan", "inf", "1e3", "1.", ".5", "+1", "0x10", ""] {
+        let run = run_script("num_error.lox", &format!("num({:?});", text));
+
+        assert_eq!(run.code, Some(70), "num({:?})", text);
+        assert!(
+            run.stderr
+                .contains(&format!("Can't convert {:?} to a number.", text)),
+            "num({:?}): {}",
+            text,
+            run.stderr
+        );
+    }
+}
+
+#[test]
+fn len_counts_characters_items_and_entries() {
+    let source = "print len(\"héllo\");\nprint len([1, 2, 3]);\nprint len({\"a\": 1});\n";
+
+    assert_eq!(output_of("len.lox", source), "5\n3\n1\n");
+
+    let run = run_script("len_error.lox", "len(1);");
+    assert_eq!(run.code, Some(70));
+    assert!(run.stderr.contains("1 at ) A number has no length."));
+}
+
+#[test]
+fn input_reads_lines_until_the_end() {
+    let source = "print input(\"name?\");\nprint input();\nprint input();\n";
+
+    let run = run_script_with_input("input.lox", source, "ada\r\nlovelace\n");
+
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(run.stdout, "name?ada\nlovelace\nnil\n");
+}
+
+#[test]
+fn natives_check_their_argument_count() {
+    let cases = [
+        ("clock(1);", "Expected 0 arguments but got 1."),
+        ("str();", "Expected 1 arguments but got 0."),
+        ("len(1, 2);", "Expected 1 arguments but got 2."),
+    ];
+
+    for (source, message) in cases {
+        let run = run_script("native_arity.lox", source);
+
+        assert_eq!(run.code, Some(70), "{}", source);
+        assert!(run.stderr.contains(message), "{}: {}", source, run.stderr);
+    }
+}
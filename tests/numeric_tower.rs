@@ -0,0 +1,100 @@
+mod common;
+
+use common::run_script;
+
+// variables the expressions can use, for numbers that can't be written as literals. `huge` is
+// 1e200, which overflows to infinity when squared
+fn prelude() -> String {
+    format!("var huge = 1{}.0;\n", "0".repeat(200))
+}
+
+// expression, what `print` shows for it
+const CONFORMANCE: &[(&str, &str)] = &[
+    // printing
+    ("1", "1"),
+    ("1.0", "1.0"),
+    ("2.5", "2.5"),
+    ("-0", "0"),
+    ("0.1 + 0.2", "0.30000000000000004"),
+    ("10000000000000000.0", "10000000000000000.0"),
+    ("0.0000001", "0.0000001"),
+    ("-0.0", "-0.0"),
+    ("huge * huge", "inf"),
+    ("-huge * huge", "-inf"),
+    ("huge * huge - huge * huge", "nan"),
+    // integer arithmetic stays integral
+    ("1 + 2", "3"),
+    ("5 - 8", "-3"),
+    ("4 * 3", "12"),
+    ("-(7)", "-7"),
+    ("9223372036854775807 - 1", "9223372036854775806"),
+    // division always gives a float
+    ("7 / 2", "3.5"),
+    ("6 / 3", "2.0"),
+    ("-7 / 2", "-3.5"),
+    ("1 / 3", "0.3333333333333333"),
+    // a float operand promotes the other
+    ("1 + 2.0", "3.0"),
+    ("2.5 * 2", "5.0"),
+    ("10 - 0.5", "9.5"),
+    ("-(1.5)", "-1.5"),
+    // equality is by value across kinds
+    ("1 == 1.0", "true"),
+    ("1.0 == 1", "true"),
+    ("1 != 1.0", "false"),
+    ("1 == 1.5", "false"),
+    ("0 == -0.0", "true"),
+    ("9007199254740993 == 9007199254740992.0", "false"),
+    ("9007199254740992 == 9007199254740992.0", "true"),
+    // so is comparison
+    ("1 < 1.5", "true"),
+    ("2 > 1.5", "true"),
+    ("1 <= 1.0", "true"),
+    ("1.0 >= 1", "true"),
+    ("-1 < -0.5", "true"),
+    ("9007199254740993 > 9007199254740992.0", "true"),
+    ("3 < 2", "false"),
+];
+
+#[test]
+fn numeric_tower_conformance() {
+    let source: String = CONFORMANCE
+        .iter()
+        .map(|(expression, _)| format!("print {};\n", expression))
+        .collect();
+    let source = prelude() + &source;
+
+    let run = run_script("numeric_tower.lox", &source);
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+
+    let printed: Vec<&str> = run.stdout.lines().collect();
+    assert_eq!(printed.len(), CONFORMANCE.len());
+
+    for ((expression, expected), actual) in CONFORMANCE.iter().zip(printed) {
+        assert_eq!(actual, *expected, "print {};", expression);
+    }
+}
+
+#[test]
+fn numeric_errors() {
+    let cases = [
+        ("9223372036854775807 + 1", "Integer overflow."),
+        ("-9223372036854775807 - 2", "Integer overflow."),
+        ("4611686018427387904 * 2", "Integer overflow."),
+        ("1 / 0", "Division by zero."),
+        ("1.5 / 0.0", "Division by zero."),
+        ("1 < \"2\"", "Operands must be numbers."),
+    ];
+
+    for (expression, message) in cases {
+        let run = run_script("numeric_error.lox", &format!("print {};", expression));
+
+        assert_eq!(run.code, Some(70), "print {};", expression);
+        assert!(
+            run.stderr.contains(message),
+            "print {}; reported {}",
+            expression,
+            run.stderr
+        );
+    }
+}
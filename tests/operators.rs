@@ -0,0 +1,54 @@
+mod common;
+
+use common::{output_of, run_script};
+
+#[test]
+fn only_nil_and_false_are_falsey() {
+    let source = "print !nil;\nprint !false;\nprint !0;\nprint !\"\";\nprint ![];\nif (0) print \"0 is truthy\";\nprint nil or \"default\";\nprint 0 and \"both\";\n";
+
+    assert_eq!(
+        output_of("truthiness.lox", source),
+        "true\ntrue\nfalse\nfalse\nfalse\n0 is truthy\ndefault\nboth\n"
+    );
+}
+
+#[test]
+fn values_of_different_types_are_never_equal() {
+    let source = "print nil == nil;\nprint nil == false;\nprint 0 == false;\nprint 1 == \"1\";\nprint \"a\" == \"a\";\nprint nil != 0;\n";
+
+    assert_eq!(
+        output_of("equality.lox", source),
+        "true\nfalse\nfalse\nfalse\ntrue\ntrue\n"
+    );
+}
+
+#[test]
+fn plus_concatenates_strings() {
+    assert_eq!(
+        output_of(
+            "concat.lox",
+            "var name = \"lox\";\nprint \"hello \" + name + \"!\";\n"
+        ),
+        "hello lox!\n"
+    );
+}
+
+#[test]
+fn bad_operands_are_reported_at_the_operator() {
+    let cases = [
+        ("print 1 - \"a\";", "1 at - Operands must be numbers."),
+        ("print nil < 1;", "1 at < Operands must be numbers."),
+        (
+            "print \"a\" + 1;",
+            "1 at + Operands must be two numbers or two strings.",
+        ),
+        ("print -\"a\";", "1 at - Operand must be a number."),
+    ];
+
+    for (source, message) in cases {
+        let run = run_script("bad_operands.lox", source);
+
+        assert_eq!(run.code, Some(70), "{}", source);
+        assert!(run.stderr.contains(message), "{}: {}", source, run.stderr);
+    }
+}
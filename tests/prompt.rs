@@ -0,0 +1,53 @@
+mod common;
+
+use common::{run_prompt, run_script};
+
+#[test]
+fn unfinished_input_continues_on_the_next_line() {
+    let input = "fun add(a,\n  b) {\n  return a + b;\n}\nprint add(1,\n  2);\nvar s = \"one\ntwo\";\nprint s;\n";
+
+    let run = run_prompt(&[], input);
+
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert!(run.stderr.is_empty(), "{}", run.stderr);
+    assert_eq!(
+        run.stdout,
+        ">\n...\n...\n...\n>\n...\n3\n>\n...\n>\none\ntwo\n>\n"
+    );
+}
+
+#[test]
+fn a_syntax_error_is_reported_without_waiting_for_more() {
+    let run = run_prompt(&[], "print 1 +;\nprint 2;\n");
+
+    assert!(run.stderr.contains("Expect expression."), "{}", run.stderr);
+    assert_eq!(run.stdout, ">\n>\n2\n>\n");
+}
+
+#[test]
+fn input_still_unfinished_at_the_end_is_reported() {
+    let run = run_prompt(&[], "{\n  print 1;\n");
+
+    assert_eq!(
+        run.stderr,
+        "Parsing error `1 at { Unclosed '{'. Expect '}' after block. Found end of input instead.`\n"
+    );
+}
+
+#[test]
+fn scripts_that_end_unfinished_are_syntax_errors() {
+    let cases = [
+        ("print \"abc;\n", "Scanner error `2 Unterminated string.`\n"),
+        (
+            "fun f() {\n  print 1;\n",
+            "Parsing error `1 at { Unclosed '{'. Expect '}' after block. Found end of input instead.`\n",
+        ),
+    ];
+
+    for (source, message) in cases {
+        let run = run_script("unfinished.lox", source);
+
+        assert_eq!(run.code, Some(65), "{}", source);
+        assert_eq!(run.stderr, message);
+    }
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::run_script;
+
+// runs a script the resolver rejects, returning what it reported
+fn resolve_error(name: &str, source: &str) -> String {
+    let run = run_script(name, source);
+    assert_eq!(run.code, Some(65), "{}", run.stdout);
+    assert!(run.stdout.is_empty(), "ran anyway: {}", run.stdout);
+
+    run.stderr
+}
+
+#[test]
+fn a_local_cant_be_read_in_its_own_initializer() {
+    let stderr = resolve_error("own_initializer.lox", "{\n  var a = a;\n}\n");
+
+    assert!(stderr.contains("2 at a Can't read local variable in its own initializer."));
+}
+
+#[test]
+fn locals_cant_be_redeclared_in_the_same_scope() {
+    let stderr = resolve_error(
+        "duplicate_local.lox",
+        "{\n  var a = 1;\n  var a = 2;\n  print a;\n}\n",
+    );
+
+    assert!(stderr.contains("3 at a Already a variable with this name in this scope."));
+}
+
+#[test]
+fn top_level_code_cant_return() {
+    let stderr = resolve_error("top_level_return.lox", "print 1;\nreturn 1;\n");
+
+    assert!(stderr.contains("2 at return Can't return from top-level code."));
+}
+
+#[test]
+fn initializers_cant_return_a_value() {
+    let stderr = resolve_error(
+        "init_return.lox",
+        "class A {\n  init() {\n    return 1;\n  }\n}\n",
+    );
+
+    assert!(stderr.contains("3 at return Can't return a value from an initializer."));
+}
+
+#[test]
+fn unused_locals_are_warned_about_but_still_run() {
+    let run = run_script(
+        "unused_local.lox",
+        "{\n  var unused = 1;\n  var used = 2;\n  print used;\n}\n",
+    );
+
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(run.stdout, "2\n");
+    assert_eq!(
+        run.stderr,
+        "Warning `2 at unused Local variable 'unused' is never used.`\n"
+    );
+}
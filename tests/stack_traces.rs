@@ -0,0 +1,31 @@
+mod common;
+
+use common::run_script;
+
+#[test]
+fn runtime_errors_list_the_calls_in_progress() {
+    let source =
+        "fun inner(x) {\n  return x + nil;\n}\nfun outer(x) {\n  return inner(x);\n}\nouter(1);\n";
+
+    let run = run_script("trace.lox", source);
+    assert_eq!(run.code, Some(70));
+
+    let lines: Vec<&str> = run.stderr.lines().collect();
+    assert!(lines[0].contains("2 at + Operands must be two numbers or two strings."));
+    assert!(lines[1].contains("at inner (") && lines[1].ends_with("trace.lox:2)"));
+    assert!(lines[2].contains("at outer (") && lines[2].ends_with("trace.lox:5)"));
+    assert!(lines[3].contains("at <script> (") && lines[3].ends_with("trace.lox:7)"));
+}
+
+#[test]
+fn a_call_with_the_wrong_argument_count_is_not_a_frame() {
+    let source = "fun f(a) {\n  return a;\n}\nf();\n";
+
+    let run = run_script("arity_trace.lox", source);
+    assert_eq!(run.code, Some(70));
+
+    let lines: Vec<&str> = run.stderr.lines().collect();
+    assert!(lines[0].contains("4 at ) Expected 1 arguments but got 0."));
+    assert!(lines[1].contains("at <script> (") && lines[1].ends_with("arity_trace.lox:4)"));
+    assert_eq!(lines.len(), 2);
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::run_script;
+
+// runs a script that shouldn't parse, returning what was reported
+fn parse_error(name: &str, source: &str) -> String {
+    let run = run_script(name, source);
+    assert_eq!(run.code, Some(65), "{}", run.stderr);
+    assert!(run.stdout.is_empty(), "{}", run.stdout);
+
+    run.stderr
+}
+
+#[test]
+fn assignment_in_a_condition_suggests_equality() {
+    let stderr = parse_error("assign_condition.lox", "var a = 1;\nif (a = 2) print a;\n");
+
+    assert!(
+        stderr.contains("2 at a Use '==' to compare values in a condition, '=' assigns."),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn a_binary_operator_without_a_left_operand_is_named() {
+    let stderr = parse_error("missing_left.lox", "print 1;\nprint * 3;\n");
+
+    assert!(
+        stderr.contains("2 at * Missing left-hand operand before '*'."),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn an_unclosed_parenthesis_points_back_at_it() {
+    let stderr = parse_error("unclosed.lox", "print (1 + 2;\n");
+
+    assert!(
+        stderr.contains("1 at ( Unclosed '('. Expect ')' after expression."),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::{run_script_with, Run};
+
+// the tree `--emit sexpr` prints for a script with syntax errors
+fn emit_broken(name: &str, source: &str) -> Run {
+    let run = run_script_with(&["--emit", "sexpr"], name, source);
+    assert_eq!(run.code, Some(65), "{}", run.stderr);
+
+    run
+}
+
+#[test]
+fn stray_characters_become_error_nodes() {
+    let run = emit_broken("stray.lox", "print ^;\nprint @ + 1;\nprint \"ok\";\n");
+
+    assert_eq!(
+        run.stdout,
+        "(print (error \"1 Unexpected character '^'.\"))\n\
+         (print (+ (error \"2 Unexpected character '@'.\") 1))\n\
+         (print \"ok\")\n"
+    );
+    assert_eq!(
+        run.stderr,
+        "1 Unexpected character '^'.\n2 Unexpected character '@'.\n"
+    );
+}
+
+#[test]
+fn a_missing_initializer_keeps_the_variable() {
+    let run = emit_broken("initializer.lox", "var x = ;\nprint x;\n");
+
+    assert_eq!(
+        run.stdout,
+        "(var x (error \"1 at ; Expect expression.\"))\n(print x)\n"
+    );
+}
+
+#[test]
+fn a_broken_parameter_list_keeps_the_function() {
+    let run = emit_broken(
+        "parameters.lox",
+        "fun g( {\n  return 1;\n}\nfun h(a b) { print a; }\n",
+    );
+
+    assert_eq!(run.stdout, "(fun g () (return 1))\n(fun h () (print a))\n");
+    assert!(
+        run.stderr.starts_with("1 at { Expect parameter name.\n"),
+        "{}",
+        run.stderr
+    );
+}
+
+#[test]
+fn a_missing_left_operand_error_node_has_its_location() {
+    let run = emit_broken("left_operand.lox", "print 1;\nprint * 3;\n");
+
+    assert_eq!(
+        run.stdout,
+        "(print 1)\n(print (error \"2 at * Missing left-hand operand before '*'.\"))\n"
+    );
+}
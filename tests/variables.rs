@@ -0,0 +1,57 @@
+mod common;
+
+use common::run_script;
+
+#[test]
+fn undefined_variables_are_reported_where_theyre_used() {
+    let source = "var a = 1;\nfun f() {\n  return missing;\n}\nf();\n";
+
+    let run = run_script("undefined_use.lox", source);
+
+    assert_eq!(run.code, Some(70));
+    let lines: Vec<&str> = run.stderr.lines().collect();
+    assert!(lines[0].contains("3 at missing Undefined variable 'missing'."));
+    assert!(lines[1].contains("at f (") && lines[1].ends_with("undefined_use.lox:3)"));
+}
+
+#[test]
+fn assigning_to_an_undeclared_global_is_an_error() {
+    let run = run_script("undeclared_assign.lox", "var a = 1;\nb = 2;\n");
+
+    assert_eq!(run.code, Some(70));
+    assert!(run.stderr.contains("2 at b Undefined variable 'b'."));
+}
+
+#[test]
+fn globals_can_be_redeclared() {
+    let run = run_script(
+        "global_redeclare.lox",
+        "var a = 1;\nvar a = a + 1;\nprint a;\n",
+    );
+
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(run.stdout, "2\n");
+}
+
+#[test]
+fn locals_shadow_outer_variables_without_changing_them() {
+    let source = "var a = \"global\";\n{\n  var a = \"outer\";\n  {\n    var a = \"inner\";\n    print a;\n  }\n  print a;\n}\nprint a;\n";
+
+    let run = run_script("shadowing.lox", source);
+
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert_eq!(run.stdout, "inner\nouter\nglobal\n");
+}
+
+#[test]
+fn locals_cant_be_redeclared_in_the_same_block() {
+    let run = run_script(
+        "local_redeclare.lox",
+        "{\n  var a = 1;\n  var a = 2;\n  print a;\n}\n",
+    );
+
+    assert_eq!(run.code, Some(65));
+    assert!(run
+        .stderr
+        .contains("3 at a Already a variable with this name in this scope."));
+}